@@ -20,15 +20,14 @@ if (!fs.existsSync(RESULTS_DIR)) {
   fs.mkdirSync(RESULTS_DIR, { recursive: true });
 }
 
-const frameworks = [
-  { name: 'react', port: 3001, service: 'react', type: 'javascript' },
-  { name: 'vue', port: 3002, service: 'vue', type: 'javascript' },
-  { name: 'angular', port: 3003, service: 'angular', type: 'javascript' },
-  { name: 'leptos', port: 3004, service: 'leptos', type: 'rust' },
-  { name: 'yew', port: 3005, service: 'yew', type: 'rust' },
-  { name: 'dioxus', port: 3006, service: 'dioxus', type: 'rust' },
-  { name: 'blade', port: 3007, service: 'blade', type: 'php' }
-];
+// Every implementation in implementations.toml, by its Docker service
+const frameworks = require('./implementations.json').map((impl) => ({
+  name: impl.name,
+  port: impl.port,
+  health: impl.health,
+  service: impl.name,
+  type: impl.language
+}));
 
 function runCommand(command, description, cwd = ROOT_DIR) {
   console.log(`\n▶️  ${description}...`);
@@ -56,7 +55,7 @@ function runCommandSilent(command, cwd = ROOT_DIR) {
   }
 }
 
-async function waitForServer(port, maxAttempts = 60) {
+async function waitForServer(port, path, maxAttempts = 60) {
   const http = require('http');
   
   for (let i = 0; i < maxAttempts; i++) {
@@ -66,7 +65,7 @@ async function waitForServer(port, maxAttempts = 60) {
           {
             hostname: 'localhost',
             port: port,
-            path: '/',
+            path: path,
             timeout: 2000
           },
           (res) => {
@@ -288,10 +287,16 @@ function analyzeBundleSize(framework) {
     
     // Copy files from container
     const containerName = `frontend-benchmark-${framework.name}`;
-    const sourcePath = framework.name === 'blade' ? '/var/www/html' : '/usr/share/nginx/html';
+    const sourcePaths = {
+      blade: '/var/www/html',
+      'leptos-ssr': '/app/dist-hydrate',
+      'yew-ssr': '/app/dist-hydrate',
+      'dioxus-fullstack': '/app/public'
+    };
+    const sourcePath = sourcePaths[framework.name] || '/usr/share/nginx/html';
     execSync(`docker cp ${containerName}:${sourcePath} ${tempDir}/`, { stdio: 'inherit' });
     
-    const distPath = path.join(tempDir, 'html');
+    const distPath = path.join(tempDir, path.basename(sourcePath));
     
     let totalJS = 0;
     let totalCSS = 0;
@@ -392,7 +397,7 @@ async function benchmarkFramework(framework) {
   
   // Wait for server to be ready
   console.log(`\n⏳ Waiting for ${framework.name} server to be ready...`);
-  const serverReady = await waitForServer(framework.port);
+  const serverReady = await waitForServer(framework.port, framework.health);
   
   if (!serverReady) {
     console.error(`   ❌ Server failed to start on port ${framework.port}`);
@@ -19,15 +19,13 @@ if (!fs.existsSync(RESULTS_DIR)) {
   fs.mkdirSync(RESULTS_DIR, { recursive: true });
 }
 
-const frameworks = [
-  { name: 'react', port: 3001, service: 'react' },
-  { name: 'vue', port: 3002, service: 'vue' },
-  { name: 'angular', port: 3003, service: 'angular' },
-  { name: 'leptos', port: 3004, service: 'leptos' },
-  { name: 'yew', port: 3005, service: 'yew' },
-  { name: 'dioxus', port: 3006, service: 'dioxus' },
-  { name: 'blade', port: 3007, service: 'blade' }
-];
+// Every implementation in implementations.toml, by its Docker service
+const frameworks = require('./implementations.json').map((impl) => ({
+  name: impl.name,
+  port: impl.port,
+  health: impl.health,
+  service: impl.name
+}));
 
 function runCommand(command, description, cwd = ROOT_DIR) {
   console.log(`\n▶️  ${description}...`);
@@ -43,7 +41,7 @@ function runCommand(command, description, cwd = ROOT_DIR) {
   }
 }
 
-async function waitForServer(port, maxAttempts = 30) {
+async function waitForServer(port, path, maxAttempts = 30) {
   const http = require('http');
   
   for (let i = 0; i < maxAttempts; i++) {
@@ -53,7 +51,7 @@ async function waitForServer(port, maxAttempts = 30) {
           {
             hostname: 'localhost',
             port: port,
-            path: '/',
+            path: path,
             timeout: 2000
           },
           (res) => {
@@ -252,7 +250,7 @@ async function benchmarkFramework(framework) {
   
   // Wait for server to be ready
   console.log(`\n⏳ Waiting for ${framework.name} server to be ready...`);
-  const serverReady = await waitForServer(framework.port);
+  const serverReady = await waitForServer(framework.port, framework.health);
   
   if (!serverReady) {
     console.error(`   ❌ Server failed to start on port ${framework.port}`);
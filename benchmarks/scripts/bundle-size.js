@@ -9,7 +9,7 @@ const fs = require('fs');
 const path = require('path');
 const { execSync } = require('child_process');
 
-const IMPLEMENTATIONS_DIR = path.join(__dirname, '../../implementations');
+const ROOT_DIR = path.join(__dirname, '../..');
 const RESULTS_DIR = path.join(__dirname, '../results');
 
 // Ensure results directory exists
@@ -71,16 +71,15 @@ function getGzippedSize(filePath) {
 /**
  * Analyze a framework implementation
  */
-function analyzeImplementation(frameworkName) {
-  const implPath = path.join(IMPLEMENTATIONS_DIR, frameworkName);
-  const distPath = path.join(implPath, 'dist');
+function analyzeImplementation(impl) {
+  const distPath = impl.dist ? path.join(ROOT_DIR, impl.dist) : null;
   
-  console.log(`\nAnalyzing ${frameworkName}...`);
+  console.log(`\nAnalyzing ${impl.name}...`);
   
   const result = {
-    framework: frameworkName,
+    framework: impl.name,
     timestamp: new Date().toISOString(),
-    exists: fs.existsSync(distPath),
+    exists: distPath !== null && fs.existsSync(distPath),
     distSize: 0,
     jsFiles: [],
     cssFiles: [],
@@ -92,7 +91,9 @@ function analyzeImplementation(frameworkName) {
   };
   
   if (!result.exists) {
-    console.log(`  ⚠️  No dist directory found. Run build first.`);
+    console.log(distPath
+      ? `  ⚠️  No dist directory found. Build it first: (cd ${impl.dir} && ${impl.build})`
+      : `  ⚠️  No static build to measure.`);
     return result;
   }
   
@@ -164,11 +165,12 @@ async function main() {
   console.log('🔍 Frontend Framework Bundle Size Analysis\n');
   console.log('=' .repeat(60));
   
-  const frameworks = ['react', 'vue', 'angular', 'blade', 'leptos', 'yew', 'dioxus'];
+  // Every implementation in implementations.toml
+  const implementations = require('./implementations.json');
   const results = [];
   
-  for (const framework of frameworks) {
-    const result = analyzeImplementation(framework);
+  for (const impl of implementations) {
+    const result = analyzeImplementation(impl);
     results.push(result);
   }
   
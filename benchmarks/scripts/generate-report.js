@@ -28,7 +28,10 @@ function safe(v, fallback = 'N/A') {
 function generateReport() {
   console.log('\n📝 Generating benchmark report...\n');
 
-  const comprehensivePath = path.join(RESULTS_DIR, 'comprehensive-benchmark-results.json');
+  // Another results file in the same format, e.g. from `cargo xtask bench`
+  const comprehensivePath = process.argv[2]
+    ? path.resolve(process.argv[2])
+    : path.join(RESULTS_DIR, 'comprehensive-benchmark-results.json');
   if (!fs.existsSync(comprehensivePath)) {
     console.error('No comprehensive results file found: ' + comprehensivePath);
     process.exit(1);
@@ -12,6 +12,9 @@ const chromeLauncher = require('chrome-launcher');
 
 const RESULTS_DIR = path.join(__dirname, '../results');
 
+// Every implementation in implementations.toml
+const implementations = require('./implementations.json');
+
 // Ensure results directory exists
 if (!fs.existsSync(RESULTS_DIR)) {
   fs.mkdirSync(RESULTS_DIR, { recursive: true });
@@ -87,19 +90,21 @@ async function runLighthouse(url, name) {
 async function main() {
   console.log('🚀 Frontend Framework Lighthouse Benchmarks\n');
   console.log('=' .repeat(60));
-  console.log('\n⚠️  Note: This requires running dev servers on specific ports:');
-  console.log('   - React: http://localhost:5173');
-  console.log('   - Vue: http://localhost:5174');
-  console.log('   - Angular: http://localhost:4200');
-  console.log('   - Blade: http://localhost:8000');
-  console.log('\n   Or use production builds served via static server.\n');
+  console.log('\n⚠️  Note: This requires the servers of implementations.toml on their ports');
+  console.log('   (`docker compose up -d`), e.g.:');
+  implementations.slice(0, 3).forEach(impl => console.log(`   - ${impl.title}: ${impl.url}`));
+  console.log('\n   Or pass name=url arguments for servers elsewhere.\n');
   
-  const frameworks = [
-    { name: 'React', url: 'http://localhost:5173' },
-    { name: 'Vue', url: 'http://localhost:5174' },
-    { name: 'Angular', url: 'http://localhost:4200' },
-    { name: 'Blade', url: 'http://localhost:8000' }
-  ];
+  // `name=url` arguments replace the default targets (used by `cargo xtask bench`)
+  const targets = process.argv.slice(2).map(arg => {
+    const at = arg.indexOf('=');
+    return { name: arg.slice(0, at), url: arg.slice(at + 1) };
+  });
+  const frameworks = targets.length > 0 ? targets : implementations.map(impl => ({
+    name: impl.title,
+    url: impl.url,
+    health: impl.health
+  }));
   
   const results = [];
   
@@ -114,7 +119,7 @@ async function main() {
           {
             hostname: url.hostname,
             port: url.port,
-            path: '/',
+            path: framework.health || '/',
             timeout: 2000
           },
           (res) => {
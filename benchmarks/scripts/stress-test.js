@@ -3,10 +3,14 @@
 /**
  * Stress test script using autocannon to measure req/s, latency, and errors
  * Runs concurrency levels against each framework service and saves results.
+ *
+ * With --open-loop it runs benchmarks/tools/load-gen at fixed request rates
+ * instead, with latencies corrected for coordinated omission; --page (fetch
+ * each page with its .js and .wasm) and --http2 are passed on to it.
  */
 
 const autocannon = require('autocannon');
-const { execSync } = require('child_process');
+const { execSync, execFileSync } = require('child_process');
 const fs = require('fs');
 const path = require('path');
 
@@ -14,18 +18,25 @@ const ROOT_DIR = path.join(__dirname, '../..');
 const RESULTS_DIR = path.join(__dirname, '../results');
 if (!fs.existsSync(RESULTS_DIR)) fs.mkdirSync(RESULTS_DIR, { recursive: true });
 
-const frameworks = [
-  { name: 'react', port: 3001, service: 'react', url: 'http://localhost:3001' },
-  { name: 'vue', port: 3002, service: 'vue', url: 'http://localhost:3002' },
-  { name: 'angular', port: 3003, service: 'angular', url: 'http://localhost:3003' },
-  { name: 'leptos', port: 3004, service: 'leptos', url: 'http://localhost:3004' },
-  { name: 'yew', port: 3005, service: 'yew', url: 'http://localhost:3005' },
-  { name: 'dioxus', port: 3006, service: 'dioxus', url: 'http://localhost:3006' },
-  { name: 'blade', port: 3007, service: 'blade', url: 'http://localhost:3007' }
-];
+// Every implementation in implementations.toml, by its Docker service
+const frameworks = require('./implementations.json').map((impl) => ({
+  name: impl.name,
+  port: impl.port,
+  health: impl.health,
+  service: impl.name,
+  url: impl.url
+}));
 
 const concurrencies = [100, 500, 1000, 2000];
 const durationSeconds = 30; // per run
+
+const args = process.argv.slice(2);
+const OPEN_LOOP = args.includes('--open-loop');
+const LOAD_GEN_FLAGS = ['--page', '--http2'].filter(flag => args.includes(flag));
+// Rates (requests or page loads per second) of the open-loop runs. Make configurable with env var STRESS_RATES=500,1000.
+const rates = process.env.STRESS_RATES ? process.env.STRESS_RATES.split(',').map(Number) : [500, 1000, 2000, 4000];
+// Most requests in flight at once in the open-loop runs; later ones wait, and are timed from when they were due
+const OPEN_LOOP_CONCURRENCY = 2000;
 // Sampling interval in ms for `docker stats`. Make configurable with env var STATS_INTERVAL_MS.
 const DEFAULT_STATS_INTERVAL_MS = 250;
 const STATS_INTERVAL_MS = process.env.STATS_INTERVAL_MS ? parseInt(process.env.STATS_INTERVAL_MS, 10) : DEFAULT_STATS_INTERVAL_MS;
@@ -39,12 +50,12 @@ function runCmd(cmd) {
   }
 }
 
-async function waitForServer(port, maxAttempts = 60) {
+async function waitForServer(port, path, maxAttempts = 60) {
   const http = require('http');
   for (let i = 0; i < maxAttempts; i++) {
     try {
       await new Promise((resolve, reject) => {
-        const req = http.get({ hostname: 'localhost', port, path: '/', timeout: 2000 }, (res) => {
+        const req = http.get({ hostname: 'localhost', port, path, timeout: 2000 }, (res) => {
           resolve();
         });
         req.on('error', reject);
@@ -85,6 +96,20 @@ function runAutocannon(url, connections, durationSeconds) {
   });
 }
 
+// One open-loop run; load-gen samples the container's stats itself, from one `docker stats` process
+function runLoadGen(url, rate, containerName) {
+  const out = execFileSync('cargo', [
+    'run', '--release', '--quiet', '--package', 'load-gen', '--',
+    url,
+    '--rate', String(rate),
+    '--duration', String(durationSeconds),
+    '--concurrency', String(OPEN_LOOP_CONCURRENCY),
+    '--container', containerName,
+    ...LOAD_GEN_FLAGS
+  ], { cwd: ROOT_DIR, encoding: 'utf8', stdio: ['ignore', 'pipe', 'inherit'], maxBuffer: 16 * 1024 * 1024 });
+  return JSON.parse(out);
+}
+
 function sampleContainerStats(containerName, durationSeconds, intervalMs = DEFAULT_STATS_INTERVAL_MS) {
   const stats = { cpuPercent: [], memoryMB: [], memoryPercent: [] };
   const iterCount = Math.max(1, Math.ceil(durationSeconds * 1000 / intervalMs));
@@ -151,7 +176,7 @@ async function runStressTest() {
     startContainer(f.service);
 
     // wait for server
-    const started = await waitForServer(f.port, 40);
+    const started = await waitForServer(f.port, f.health, 40);
     if (!started) {
       console.error(`Server ${f.name} did not start on port ${f.port}`);
       stopContainer(f.service);
@@ -161,7 +186,21 @@ async function runStressTest() {
     // Run tests for multiple concurrencies
     const fwResult = { framework: f.name, url: f.url, samples: [] };
 
-    for (const c of concurrencies) {
+    for (const rate of OPEN_LOOP ? rates : []) {
+      console.log(`\n   🎯 Running load-gen: ${rate}/s for ${durationSeconds}s`);
+      try {
+        const sample = runLoadGen(f.url, rate, `frontend-benchmark-${f.service}`);
+        console.log(`   ✅ ${f.name} @ ${rate}/s => ${Math.round(sample.requests.average)} ${sample.unit}s/s, p50 ${Math.round(sample.latency.p50)}ms, p99 ${Math.round(sample.latency.p99)}ms, p99.9 ${Math.round(sample.latency.p99_9)}ms`);
+        fwResult.samples.push(sample);
+      } catch (err) {
+        console.error(`   ❌ Error running load-gen: ${err.message || err}`);
+        fwResult.samples.push({ concurrency: OPEN_LOOP_CONCURRENCY, rate, error: err.message || 'load-gen error' });
+      }
+
+      await new Promise((res) => setTimeout(res, 2000)); // short cooldown
+    }
+
+    for (const c of OPEN_LOOP ? [] : concurrencies) {
       console.log(`\n   🔫 Running autocannon: ${c} connections for ${durationSeconds}s`);
       let statsPromise; 
       try {
@@ -213,6 +252,11 @@ async function runStressTest() {
   console.log('\n🟢 Stress testing complete. Results written to /benchmarks/results/stress-test-results.json');
 }
 
+if (OPEN_LOOP) {
+  // Compile once up front, so no run waits on it
+  execSync('cargo build --release --quiet --package load-gen', { cwd: ROOT_DIR, stdio: 'inherit' });
+}
+
 runStressTest().catch((err) => {
   console.error('Fatal:', err);
   process.exit(1);
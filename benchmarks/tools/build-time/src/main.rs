@@ -0,0 +1,269 @@
+//! Measures the build metrics of `BENCHMARK_SPEC.md` for the Rust apps:
+//! clean release and dev build times, and the dev rebuild after a one-line
+//! edit to `src/main.rs`, the closest a Rust app gets to HMR.
+//!
+//! Every build runs with `--timings`, and the unit counts of its report
+//! are kept with its time. Each framework builds in its own target
+//! directory, which is removed before every clean build; dependencies are
+//! fetched beforehand so downloads are not timed.
+
+mod stats;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+use implementations::Manifest;
+use serde::Serialize;
+
+use stats::{Measurement, Run};
+
+const USAGE: &str = "\
+Usage: build-time [OPTIONS] [NAME=APP_DIR ...]
+
+Without apps, measures every Rust app in implementations.toml.
+
+Options:
+  --repeats <N>      Builds of each kind per app [default: 3]
+  --target <TRIPLE>  Target to build for [default: wasm32-unknown-unknown]
+  --out <FILE>       JSON output, - for stdout [default: benchmarks/results/build-times.json]
+  -h, --help         Print this help";
+
+/// Appended to `src/main.rs` for the incremental rebuild, then removed.
+const EDIT: &str = "\nconst _: &str = \"build-time: incremental rebuild\";\n";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    framework: String,
+    app: PathBuf,
+    target: String,
+    rustc: String,
+    timestamp: u64,
+    repeats: usize,
+    clean_release: Measurement,
+    clean_dev: Measurement,
+    incremental_dev: Measurement,
+}
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../..")
+}
+
+fn main() {
+    let mut repeats = 3;
+    let mut target = "wasm32-unknown-unknown".to_string();
+    let mut out = repo_root().join("benchmarks/results/build-times.json");
+    let mut apps = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--repeats" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => repeats = n,
+                _ => usage_error("--repeats needs a positive number"),
+            },
+            "--target" => match args.next() {
+                Some(triple) => target = triple,
+                None => usage_error("--target needs a target triple"),
+            },
+            "--out" => match args.next() {
+                Some(path) => out = PathBuf::from(path),
+                None => usage_error("--out needs a path"),
+            },
+            flag if flag.starts_with('-') => usage_error(&format!("unknown option {}", flag)),
+            app => match app.split_once('=') {
+                Some((name, dir)) => apps.push((name.to_string(), PathBuf::from(dir))),
+                None => usage_error(&format!("expected NAME=APP_DIR, got {}", app)),
+            },
+        }
+    }
+    if apps.is_empty() {
+        let manifest = Manifest::load().unwrap_or_else(|err| fail(&err));
+        apps = manifest
+            .wasm_apps()
+            .map(|app| (app.name.clone(), app.dir_path()))
+            .collect();
+    }
+    if std::env::var_os("RUSTC_WRAPPER").is_some() {
+        eprintln!("warning: RUSTC_WRAPPER is set, so clean builds may come from its cache");
+    }
+
+    let rustc = Command::new("rustc")
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    let mut reports = Vec::new();
+    for (framework, app) in apps {
+        let builder = Builder {
+            manifest: app.join("Cargo.toml"),
+            main: app.join("src/main.rs"),
+            target_dir: repo_root().join("target/build-time").join(&framework),
+            target: target.clone(),
+        };
+        let [clean_release, clean_dev, incremental_dev] = builder
+            .measure(&framework, repeats)
+            .unwrap_or_else(|err| fail(&err));
+
+        reports.push(Report {
+            framework,
+            app,
+            target: target.clone(),
+            rustc: rustc.clone(),
+            timestamp,
+            repeats,
+            clean_release: Measurement::new(clean_release),
+            clean_dev: Measurement::new(clean_dev),
+            incremental_dev: Measurement::new(incremental_dev),
+        });
+    }
+
+    let json = serde_json::to_string_pretty(&reports).expect("reports serialize to JSON");
+    if out == Path::new("-") {
+        println!("{}", json);
+        return;
+    }
+    if let Some(dir) = out.parent() {
+        if let Err(err) = std::fs::create_dir_all(dir) {
+            fail(&format!("failed to create {}: {}", dir.display(), err));
+        }
+    }
+    if let Err(err) = std::fs::write(&out, json) {
+        fail(&format!("failed to write {}: {}", out.display(), err));
+    }
+
+    for report in &reports {
+        print_summary(report);
+    }
+    println!("Results saved to {}", out.display());
+}
+
+/// Builds one app in its own target directory.
+struct Builder {
+    manifest: PathBuf,
+    main: PathBuf,
+    target_dir: PathBuf,
+    target: String,
+}
+
+impl Builder {
+    fn clean(&self) -> Result<(), String> {
+        if !self.target_dir.exists() {
+            return Ok(());
+        }
+        std::fs::remove_dir_all(&self.target_dir)
+            .map_err(|err| format!("failed to remove {}: {}", self.target_dir.display(), err))
+    }
+
+    /// Runs `cargo <args>` on the app, with its output in the error if it
+    /// fails.
+    fn cargo(&self, args: &[&str]) -> Result<(), String> {
+        let output = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+            .args(args)
+            .arg("--manifest-path")
+            .arg(&self.manifest)
+            .env("CARGO_TARGET_DIR", &self.target_dir)
+            .output()
+            .map_err(|err| format!("failed to run cargo: {}", err))?;
+        if output.status.success() {
+            return Ok(());
+        }
+        Err(format!(
+            "cargo {} for {} exited with {}:\n{}",
+            args.join(" "),
+            self.manifest.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+
+    fn build(&self, release: bool) -> Result<Run, String> {
+        let mut args = vec!["build", "--timings", "--target", &self.target];
+        if release {
+            args.push("--release");
+        }
+        let start = Instant::now();
+        self.cargo(&args)?;
+        let seconds = start.elapsed().as_secs_f64();
+
+        let timings = self.target_dir.join("cargo-timings/cargo-timing.html");
+        let units = std::fs::read_to_string(&timings)
+            .ok()
+            .and_then(|html| stats::parse_units(&html))
+            .ok_or_else(|| format!("no unit counts in {}", timings.display()))?;
+        Ok(Run { seconds, units })
+    }
+
+    /// A dev build after appending [`EDIT`] to `src/main.rs`, which is put
+    /// back as it was afterwards, whether the build succeeds or not.
+    fn rebuild_after_edit(&self) -> Result<Run, String> {
+        let original = std::fs::read_to_string(&self.main)
+            .map_err(|err| format!("failed to read {}: {}", self.main.display(), err))?;
+        std::fs::write(&self.main, format!("{}{}", original, EDIT))
+            .map_err(|err| format!("failed to edit {}: {}", self.main.display(), err))?;
+        let run = self.build(false);
+        std::fs::write(&self.main, original)
+            .map_err(|err| format!("failed to restore {}: {}", self.main.display(), err))?;
+        run
+    }
+
+    /// Every kind of build, `repeats` times each.
+    fn measure(&self, name: &str, repeats: usize) -> Result<[Vec<Run>; 3], String> {
+        self.cargo(&["fetch"])?;
+        let mut runs: [Vec<Run>; 3] = Default::default();
+        for repeat in 1..=repeats {
+            eprintln!("{} {}/{}", name, repeat, repeats);
+            self.clean()?;
+            runs[0].push(self.build(true)?);
+            self.clean()?;
+            runs[1].push(self.build(false)?);
+            runs[2].push(self.rebuild_after_edit()?);
+        }
+        Ok(runs)
+    }
+}
+
+fn print_summary(report: &Report) {
+    println!("{} ({}, {})", report.framework, report.target, report.rustc);
+    for (kind, measurement) in [
+        ("clean release", &report.clean_release),
+        ("clean dev", &report.clean_dev),
+        ("incremental dev", &report.incremental_dev),
+    ] {
+        let units = measurement
+            .runs
+            .last()
+            .map(|run| run.units)
+            .unwrap_or_default();
+        println!(
+            "  {:<16} median {:>7.2}s  spread {:>6.2}s ({:.2}-{:.2}s)  {} of {} units built",
+            kind,
+            measurement.median,
+            measurement.spread,
+            measurement.min,
+            measurement.max,
+            units.dirty,
+            units.total
+        );
+    }
+    println!();
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,115 @@
+//! What one measured build records, and the summary over its repeats.
+
+use serde::Serialize;
+
+/// Units of a build, from the summary of its `cargo build --timings`
+/// report. `dirty` ones were compiled, `fresh` ones reused.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Units {
+    pub fresh: u32,
+    pub dirty: u32,
+    pub total: u32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Run {
+    pub seconds: f64,
+    #[serde(flatten)]
+    pub units: Units,
+}
+
+/// Every run of one kind of build, with the median and spread of their
+/// wall-clock times.
+#[derive(Clone, Debug, Serialize)]
+pub struct Measurement {
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+    /// `max - min`, in seconds.
+    pub spread: f64,
+    pub runs: Vec<Run>,
+}
+
+impl Measurement {
+    pub fn new(runs: Vec<Run>) -> Self {
+        let mut seconds: Vec<f64> = runs.iter().map(|run| run.seconds).collect();
+        seconds.sort_by(f64::total_cmp);
+        let (min, max) = match (seconds.first(), seconds.last()) {
+            (Some(&min), Some(&max)) => (min, max),
+            _ => (0.0, 0.0),
+        };
+        Self {
+            median: median(&seconds),
+            min,
+            max,
+            spread: max - min,
+            runs,
+        }
+    }
+}
+
+/// Median of sorted `values`, the mean of the middle two for an even count.
+fn median(values: &[f64]) -> f64 {
+    match values.len() {
+        0 => 0.0,
+        len if len % 2 == 1 => values[len / 2],
+        len => (values[len / 2 - 1] + values[len / 2]) / 2.0,
+    }
+}
+
+/// The unit counts in the summary table of a `--timings` HTML report.
+pub fn parse_units(html: &str) -> Option<Units> {
+    let count = |label: &str| -> Option<u32> {
+        let cell = format!("<td>{}</td><td>", label);
+        let rest = &html[html.find(&cell)? + cell.len()..];
+        rest[..rest.find('<')?].trim().parse().ok()
+    };
+    Some(Units {
+        fresh: count("Fresh units:")?,
+        dirty: count("Dirty units:")?,
+        total: count("Total units:")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(seconds: f64) -> Run {
+        Run {
+            seconds,
+            units: Units::default(),
+        }
+    }
+
+    #[test]
+    fn summarizes_runs() {
+        let odd = Measurement::new(vec![run(3.0), run(1.0), run(2.0)]);
+        assert_eq!(
+            (odd.median, odd.min, odd.max, odd.spread),
+            (2.0, 1.0, 3.0, 2.0)
+        );
+        assert_eq!(odd.runs[0].seconds, 3.0);
+
+        let even = Measurement::new(vec![run(4.0), run(1.0), run(2.0), run(3.0)]);
+        assert_eq!(even.median, 2.5);
+    }
+
+    #[test]
+    fn reads_unit_counts_from_timings() {
+        let html = "<table class=\"my-table summary-table\">\n\
+            <tr><td>Profile:</td><td>release</td></tr>\n\
+            <tr><td>Fresh units:</td><td>211</td></tr>\n\
+            <tr><td>Dirty units:</td><td>1</td></tr>\n\
+            <tr><td>Total units:</td><td>212</td></tr>\n";
+        assert_eq!(
+            parse_units(html),
+            Some(Units {
+                fresh: 211,
+                dirty: 1,
+                total: 212
+            })
+        );
+        assert_eq!(parse_units("<table></table>"), None);
+    }
+}
@@ -0,0 +1,96 @@
+//! The markup tests every app runs on its native renderer: each app's
+//! `ssr.rs` renders [`Fixture::seed`] and hands the HTML to
+//! [`Fixture::check`], so the three apps test the same things.
+
+use todo_core::seed::SeedConfig;
+
+/// A seed to render and what its markup must contain.
+pub struct Fixture {
+    pub seed: SeedConfig,
+    check: fn(framework: &str, html: &str),
+}
+
+impl Fixture {
+    /// Panics if `html`, from `framework`'s renderer, is not what the
+    /// seed should produce.
+    pub fn check(&self, framework: &str, html: &str) {
+        (self.check)(framework, html);
+    }
+}
+
+/// Every fixture, for an app's test to render in turn.
+pub fn all() -> [Fixture; 3] {
+    [
+        Fixture {
+            seed: SeedConfig::default(),
+            check: default_seed,
+        },
+        Fixture {
+            seed: GENERATED,
+            check: generated_texts,
+        },
+        Fixture {
+            seed: SeedConfig {
+                count: 0,
+                ..GENERATED
+            },
+            check: empty_state,
+        },
+    ]
+}
+
+const GENERATED: SeedConfig = SeedConfig {
+    count: 5,
+    seed: 42,
+    completed_ratio: Some(0.5),
+    text_len: Some(40),
+};
+
+fn count(html: &str, needle: &str) -> usize {
+    html.matches(needle).count()
+}
+
+fn default_seed(framework: &str, html: &str) {
+    assert_eq!(
+        count(html, r#"class="todo-item"#),
+        100,
+        "{} todos",
+        framework
+    );
+    assert_eq!(
+        count(html, r#"class="todo-item completed"#),
+        33,
+        "{} completed todos",
+        framework
+    );
+    for needle in [
+        "67 items remaining",
+        &format!(r#"<span class="framework-badge">{}</span>"#, framework),
+        r#"<span class="todo-text">Todo item 1</span>"#,
+    ] {
+        assert!(html.contains(needle), "{} is missing {}", framework, needle);
+    }
+}
+
+/// Every generated text, in the order of the list.
+fn generated_texts(framework: &str, html: &str) {
+    let mut rest = html;
+    for todo in GENERATED.generate().todos {
+        let text = format!(r#"<span class="todo-text">{}</span>"#, todo.text);
+        let at = rest.find(&text).unwrap_or_else(|| {
+            panic!(
+                "{} is missing {:?} or has it out of order",
+                framework, todo.text
+            )
+        });
+        rest = &rest[at + text.len()..];
+    }
+}
+
+fn empty_state(framework: &str, html: &str) {
+    assert!(
+        html.contains("No todos to display") && !html.contains("todo-list"),
+        "{} does not render the empty state",
+        framework
+    );
+}
@@ -0,0 +1,173 @@
+//! Checks the markup of an implementation against the spec in `spec.toml`.
+//!
+//! The markup can be a server-rendered page or the serialized DOM of a
+//! client-rendered one: both are reduced to the app's elements (tag,
+//! classes, `aria-label` and own text) and compared in document order with
+//! what the spec expects for the same state.
+
+use std::fmt;
+
+pub mod fixtures;
+pub mod markup;
+pub mod spec;
+
+use markup::Element;
+use spec::Spec;
+
+/// The differences between the expected and the rendered elements.
+#[derive(Debug)]
+pub struct Report {
+    pub framework: String,
+    pub expected: usize,
+    pub found: usize,
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// Expected at this position of the spec but not rendered.
+    Missing(usize, Element),
+    /// Rendered at this position of the markup but not expected.
+    Unexpected(usize, Element),
+    /// The page does not link the shared stylesheet.
+    Stylesheet(String),
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::Missing(at, element) => write!(f, "- expected #{}: {}", at, element),
+            Difference::Unexpected(at, element) => write!(f, "+ found    #{}: {}", at, element),
+            Difference::Stylesheet(href) => {
+                write!(f, "- stylesheet: no <link rel=\"stylesheet\"> to {}", href)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} does not conform ({} elements expected, {} found):",
+            self.framework, self.expected, self.found
+        )?;
+        for difference in &self.differences {
+            writeln!(f, "  {}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Report {}
+
+/// Compares the app in `html` with what `spec` expects from `framework`
+/// for the spec's state.
+pub fn check(spec: &Spec, framework: &str, html: &str) -> Result<(), Report> {
+    let expected = spec.expected(framework, &spec.state());
+    let found = markup::app_elements(html);
+    let differences = diff(&expected, &found);
+
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(Report {
+            framework: framework.to_string(),
+            expected: expected.len(),
+            found: found.len(),
+            differences,
+        })
+    }
+}
+
+/// Checks that the page shell `index_html` links the spec's stylesheet.
+pub fn check_stylesheet(spec: &Spec, framework: &str, index_html: &str) -> Result<(), Report> {
+    let linked = markup::tags(index_html, "link").any(|link| {
+        let is_stylesheet = link.attribute("rel").is_some_and(|rel| {
+            rel.split_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+        });
+        is_stylesheet
+            && link
+                .attribute("href")
+                .is_some_and(|href| href.ends_with(&spec.stylesheet))
+    });
+
+    if linked {
+        Ok(())
+    } else {
+        Err(Report {
+            framework: framework.to_string(),
+            expected: 1,
+            found: 0,
+            differences: vec![Difference::Stylesheet(spec.stylesheet.clone())],
+        })
+    }
+}
+
+/// The edits from `expected` to `found`, from their longest common
+/// subsequence, so one missing element is reported once instead of
+/// shifting every element after it.
+fn diff(expected: &[Element], found: &[Element]) -> Vec<Difference> {
+    let (n, m) = (expected.len(), found.len());
+    // common[i][j]: length of the LCS of expected[i..] and found[j..]
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if expected[i] == found[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut differences = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == found[j] {
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && common[i + 1][j] >= common[i][j + 1]) {
+            differences.push(Difference::Missing(i, expected[i].clone()));
+            i += 1;
+        } else {
+            differences.push(Difference::Unexpected(j, found[j].clone()));
+            j += 1;
+        }
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(tag: &str, class: &str) -> Element {
+        Element::new(tag, class, None, "")
+    }
+
+    #[test]
+    fn diff_reports_each_change_once() {
+        let expected = [element("div", "a"), element("span", "b"), element("p", "c")];
+        let found = [element("div", "a"), element("p", "c"), element("i", "d")];
+
+        assert_eq!(
+            diff(&expected, &found),
+            vec![
+                Difference::Missing(1, element("span", "b")),
+                Difference::Unexpected(2, element("i", "d")),
+            ]
+        );
+    }
+
+    #[test]
+    fn stylesheet_must_be_linked() {
+        let spec = Spec::bundled();
+        let linked = r#"<head><link rel="stylesheet" href="../../shared/styles/todo.css"></head>"#;
+        let inline = r#"<head><style>.todo-app {}</style></head>"#;
+
+        assert!(check_stylesheet(&spec, "Test", linked).is_ok());
+        assert!(check_stylesheet(&spec, "Test", inline).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! A small HTML tokenizer that reduces rendered markup to the elements the
+//! spec compares.
+//!
+//! It is not a full HTML parser, only enough for server-rendered and
+//! serialized DOM markup: comments (including Yew's component markers and
+//! Leptos's `<!>`), doctypes and the contents of `<script>`/`<style>` are
+//! skipped, void elements need no closing tag, and a stray closing tag
+//! closes everything up to its opening tag.
+
+use std::fmt;
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// One element reduced to what the spec compares.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Element {
+    pub tag: String,
+    /// Sorted and deduplicated.
+    pub classes: Vec<String>,
+    pub aria_label: Option<String>,
+    /// The element's own text nodes, whitespace collapsed and trimmed.
+    pub text: String,
+}
+
+impl Element {
+    pub fn new(tag: &str, class: &str, aria_label: Option<String>, text: &str) -> Self {
+        let mut classes: Vec<String> = class.split_whitespace().map(str::to_string).collect();
+        classes.sort();
+        classes.dedup();
+        Self {
+            tag: tag.to_ascii_lowercase(),
+            classes,
+            aria_label,
+            text: collapse_whitespace(text),
+        }
+    }
+
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.tag)?;
+        if !self.classes.is_empty() {
+            write!(f, " class=\"{}\"", self.classes.join(" "))?;
+        }
+        if let Some(label) = &self.aria_label {
+            write!(f, " aria-label=\"{}\"", label)?;
+        }
+        write!(f, ">")?;
+        if !self.text.is_empty() {
+            write!(f, "{:?}", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// An opening tag with its attributes, as found in the markup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Tag {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The elements of the app: the first `div.todo-app` and its descendants,
+/// or nothing when there is none.
+pub fn app_elements(html: &str) -> Vec<Element> {
+    let elements = elements(html);
+    let Some(root) = elements
+        .iter()
+        .position(|(_, element)| element.tag == "div" && element.has_class("todo-app"))
+    else {
+        return Vec::new();
+    };
+    let root_depth = elements[root].0;
+    let inside = elements[root + 1..]
+        .iter()
+        .take_while(|(depth, _)| *depth > root_depth)
+        .count();
+    elements
+        .into_iter()
+        .skip(root)
+        .take(inside + 1)
+        .map(|(_, element)| element)
+        .collect()
+}
+
+/// Every element in document order with its nesting depth and own text.
+fn elements(html: &str) -> Vec<(usize, Element)> {
+    let mut elements: Vec<(usize, Element)> = Vec::new();
+    let mut texts: Vec<String> = Vec::new();
+    // Open elements as (tag name, index into `elements`)
+    let mut open: Vec<(String, usize)> = Vec::new();
+
+    for token in tokenize(html) {
+        match token {
+            Token::Open(tag, self_closing) => {
+                let class = tag.attribute("class").unwrap_or_default();
+                let label = tag.attribute("aria-label").map(str::to_string);
+                elements.push((open.len(), Element::new(&tag.name, class, label, "")));
+                texts.push(String::new());
+                if !self_closing && !VOID_ELEMENTS.contains(&tag.name.as_str()) {
+                    open.push((tag.name, elements.len() - 1));
+                }
+            }
+            Token::Close(name) => {
+                if let Some(at) = open.iter().rposition(|(open, _)| *open == name) {
+                    open.truncate(at);
+                }
+            }
+            Token::Text(text) => {
+                if let Some(&(_, index)) = open.last() {
+                    texts[index].push_str(&text);
+                }
+            }
+        }
+    }
+
+    for ((_, element), text) in elements.iter_mut().zip(texts) {
+        element.text = collapse_whitespace(&text);
+    }
+    elements
+}
+
+/// Every opening tag named `name`, in document order.
+pub fn tags<'a>(html: &'a str, name: &'a str) -> impl Iterator<Item = Tag> + 'a {
+    tokenize(html)
+        .into_iter()
+        .filter_map(move |token| match token {
+            Token::Open(tag, _) if tag.name == name => Some(tag),
+            _ => None,
+        })
+}
+
+enum Token {
+    /// An opening tag and whether it closed itself (`<br/>`).
+    Open(Tag, bool),
+    Close(String),
+    Text(String),
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if lt > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..lt])));
+        }
+        rest = &rest[lt..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            // `<!DOCTYPE html>`, Leptos's `<!>` placeholders
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(close) = rest.strip_prefix("</") {
+            let end = close.find('>').unwrap_or(close.len());
+            tokens.push(Token::Close(close[..end].trim().to_ascii_lowercase()));
+            rest = close.get(end + 1..).unwrap_or("");
+        } else if let Some((tag, self_closing, after)) = open_tag(&rest[1..]) {
+            rest = after;
+            // Raw text elements: their contents are not markup
+            if tag.name == "script" || tag.name == "style" {
+                let close = format!("</{}", tag.name);
+                let end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                rest = &rest[end..];
+            }
+            tokens.push(Token::Open(tag, self_closing));
+        } else {
+            // A lone `<` in text
+            tokens.push(Token::Text("<".into()));
+            rest = &rest[1..];
+        }
+    }
+    tokens
+}
+
+/// Parses `name attr="value" ...>` after the `<`, returning the tag,
+/// whether it ended in `/>` and the input after the `>`.
+fn open_tag(input: &str) -> Option<(Tag, bool, &str)> {
+    let name_len = input
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(input.len());
+    let name = &input[..name_len];
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut tag = Tag {
+        name: name.to_ascii_lowercase(),
+        attributes: Vec::new(),
+    };
+    let mut rest = &input[name_len..];
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Some((tag, true, after));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some((tag, false, after));
+        }
+        if let Some(after) = rest.strip_prefix('/') {
+            rest = after;
+            continue;
+        }
+        if rest.is_empty() {
+            return Some((tag, false, rest));
+        }
+
+        let attr_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let attr = rest[..attr_len].to_ascii_lowercase();
+        rest = rest[attr_len..].trim_start();
+
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (value, after) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            rest = after;
+            decode_entities(value)
+        } else {
+            String::new()
+        };
+        tag.attributes.push((attr, value));
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = match entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => entity.strip_prefix('#')?.parse().ok(),
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_framework_markers_and_attribute_order() {
+        let yew = r#"<!--<[App]>--><div class="todo-app"><ul class="todo-list"><li class="todo-item completed"><input checked type="checkbox" aria-label="Toggle a &amp; b" class="todo-checkbox"><span class="todo-text">a &amp; b</span></li></ul></div><!--</[App]>-->"#;
+        let leptos = r#"<div class="todo-app"><ul class="todo-list"><!><li class="completed  todo-item"><input class="todo-checkbox" aria-label="Toggle a &amp; b" type="checkbox"><span class="todo-text">
+            a &#38; b
+        </span></li><!></ul></div>"#;
+
+        assert_eq!(app_elements(yew), app_elements(leptos));
+        assert_eq!(app_elements(yew).len(), 5);
+    }
+
+    #[test]
+    fn keeps_only_the_app_and_its_own_text() {
+        let html = r#"<!DOCTYPE html><html><head><script>if (a < b) {}</script></head><body><div id="main"><div class="todo-app"><label class="toggle-all-label"><input class="toggle-all"/>Mark all</label></div></div><p>after</p></body></html>"#;
+
+        let elements = app_elements(html);
+        assert_eq!(
+            elements,
+            vec![
+                Element::new("div", "todo-app", None, ""),
+                Element::new("label", "toggle-all-label", None, "Mark all"),
+                Element::new("input", "toggle-all", None, ""),
+            ]
+        );
+    }
+}
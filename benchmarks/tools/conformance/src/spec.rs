@@ -0,0 +1,112 @@
+//! The machine-readable spec in `spec.toml`.
+
+use serde::Deserialize;
+use todo_core::seed::SeedConfig;
+use todo_core::{remaining_label, Todo, TodoState};
+
+use crate::markup::Element;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Spec {
+    /// Path, relative to the repository root, of the stylesheet every
+    /// `index.html` must link.
+    pub stylesheet: String,
+    /// The state to render, as `name=value` seed arguments.
+    pub seed: String,
+    pub before: Vec<ElementSpec>,
+    /// Repeated for every visible todo.
+    pub row: Vec<ElementSpec>,
+    pub after: Vec<ElementSpec>,
+}
+
+/// One expected element; `class`, `aria` and `text` may hold placeholders.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ElementSpec {
+    pub tag: String,
+    #[serde(default)]
+    pub class: String,
+    pub aria: Option<String>,
+    #[serde(default)]
+    pub text: String,
+}
+
+impl Spec {
+    /// The spec shipped with this crate.
+    pub fn bundled() -> Self {
+        Self::parse(include_str!("../spec.toml")).expect("spec.toml is invalid")
+    }
+
+    pub fn parse(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+
+    /// The seed arguments, one `name=value` per item.
+    pub fn seed_args(&self) -> Vec<String> {
+        self.seed.split_whitespace().map(str::to_string).collect()
+    }
+
+    /// The state the apps render for [`Spec::seed_args`].
+    pub fn state(&self) -> TodoState {
+        SeedConfig::from_args(self.seed_args()).generate()
+    }
+
+    /// The elements an implementation called `framework` must render for
+    /// `state`, in document order.
+    pub fn expected(&self, framework: &str, state: &TodoState) -> Vec<Element> {
+        let remaining = remaining_label(state.remaining_count());
+        let page = Placeholders {
+            framework,
+            remaining: &remaining,
+            todo: None,
+        };
+
+        let mut elements: Vec<Element> =
+            self.before.iter().map(|spec| spec.expand(&page)).collect();
+        for todo in state.filtered() {
+            let row = Placeholders {
+                todo: Some(todo),
+                ..page
+            };
+            elements.extend(self.row.iter().map(|spec| spec.expand(&row)));
+        }
+        elements.extend(self.after.iter().map(|spec| spec.expand(&page)));
+        elements
+    }
+}
+
+impl ElementSpec {
+    fn expand(&self, placeholders: &Placeholders) -> Element {
+        Element::new(
+            &self.tag,
+            &placeholders.substitute(&self.class),
+            self.aria
+                .as_deref()
+                .map(|aria| placeholders.substitute(aria)),
+            &placeholders.substitute(&self.text),
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Placeholders<'a> {
+    framework: &'a str,
+    remaining: &'a str,
+    todo: Option<&'a Todo>,
+}
+
+impl Placeholders<'_> {
+    fn substitute(&self, template: &str) -> String {
+        let (text, completed) = match self.todo {
+            Some(todo) => (
+                todo.text.as_str(),
+                if todo.completed { "completed" } else { "" },
+            ),
+            None => ("", ""),
+        };
+        template
+            .replace("{framework}", self.framework)
+            .replace("{remaining}", self.remaining)
+            .replace("{completed}", completed)
+            .replace("{text}", text)
+    }
+}
@@ -0,0 +1,70 @@
+//! Runs the conformance check against the Rust apps of
+//! `implementations.toml`.
+//!
+//! Each app is built natively and run with the spec's seed arguments,
+//! which prints the markup its server-side renderer produces. Set
+//! `CONFORMANCE_MARKUP_DIR` to a directory holding `<app>.html` files
+//! (e.g. serialized DOM saved from a browser) to check those instead.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use conformance::spec::Spec;
+use implementations::{Implementation, Manifest};
+
+fn markup(app: &Implementation, spec: &Spec) -> String {
+    if let Some(dir) = std::env::var_os("CONFORMANCE_MARKUP_DIR") {
+        let path = PathBuf::from(dir).join(format!("{}.html", app.name));
+        return std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("failed to read {}: {}", path.display(), err));
+    }
+
+    let manifest = app.dir_path().join("Cargo.toml");
+    // The app's own target directory, so this does not wait on the lock
+    // of the one running the tests
+    let output = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(&manifest)
+        .env("CARGO_TARGET_DIR", app.dir_path().join("target"))
+        .arg("--")
+        .args(spec.seed_args())
+        .output()
+        .unwrap_or_else(|err| panic!("failed to run {}: {}", manifest.display(), err));
+    assert!(
+        output.status.success(),
+        "{} exited with {}:\n{}",
+        manifest.display(),
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("markup is not UTF-8")
+}
+
+/// The app's markup and page shells against the spec, with the report of
+/// the first check that fails.
+fn check(app: &Implementation, spec: &Spec) -> Result<(), String> {
+    let html = markup(app, spec);
+    conformance::check(spec, &app.title, &html).map_err(|report| report.to_string())?;
+
+    for shell in ["index.html", "index.hydrate.html"] {
+        let path = app.dir_path().join(shell);
+        let Ok(index) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        conformance::check_stylesheet(spec, &app.title, &index)
+            .map_err(|report| format!("{}: {}", path.display(), report))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn rust_apps_conform() {
+    let spec = Spec::bundled();
+    let manifest = Manifest::load().unwrap_or_else(|err| panic!("{}", err));
+
+    let failures: Vec<String> = manifest
+        .wasm_apps()
+        .filter_map(|app| check(app, &spec).err())
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
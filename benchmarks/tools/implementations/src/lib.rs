@@ -0,0 +1,249 @@
+//! Loads `implementations.toml`, the one list of implementations the
+//! benchmark tooling works from, and writes the files generated from it.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// What the tooling needs to know about every implementation.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    #[serde(rename = "implementation")]
+    pub implementations: Vec<Implementation>,
+}
+
+/// One `[[implementation]]`; see `implementations.toml` for the fields.
+/// Paths are relative to the repository root, see [`Implementation::dir_path`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Implementation {
+    pub name: String,
+    pub title: String,
+    pub language: Language,
+    pub dir: PathBuf,
+    pub build: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dist: Option<PathBuf>,
+    pub port: u16,
+    pub health: String,
+    pub context: PathBuf,
+    pub dockerfile: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Javascript,
+    Rust,
+    Php,
+}
+
+pub fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../..")
+}
+
+impl Manifest {
+    /// `implementations.toml` at the root of the repository.
+    pub fn load() -> Result<Self, String> {
+        let path = repo_root().join("implementations.toml");
+        let source = std::fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        Self::parse(&source).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// Parses a manifest and checks that names and ports are unique and
+    /// that every Rust app with a `package` has a `dist`.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let manifest: Self = toml::from_str(source).map_err(|err| err.to_string())?;
+
+        let mut names = HashSet::new();
+        let mut ports = HashSet::new();
+        for implementation in &manifest.implementations {
+            if !names.insert(&implementation.name) {
+                return Err(format!("{} is listed twice", implementation.name));
+            }
+            if !ports.insert(implementation.port) {
+                return Err(format!(
+                    "{} uses port {}, which is taken",
+                    implementation.name, implementation.port
+                ));
+            }
+            if implementation.package.is_some()
+                && (implementation.language != Language::Rust || implementation.dist.is_none())
+            {
+                return Err(format!(
+                    "{} has a package, so it must be a Rust app with a dist",
+                    implementation.name
+                ));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Implementation> {
+        self.implementations
+            .iter()
+            .find(|implementation| implementation.name == name)
+    }
+
+    /// The Rust apps `cargo xtask` builds to wasm: the ones with a
+    /// `package`.
+    pub fn wasm_apps(&self) -> impl Iterator<Item = &Implementation> {
+        self.implementations
+            .iter()
+            .filter(|implementation| implementation.package.is_some())
+    }
+
+    /// `docker-compose.yml`: one service per implementation, named after
+    /// it, serving on its port.
+    pub fn compose(&self) -> String {
+        let mut out = String::from(GENERATED_YAML);
+        out.push_str("version: '3.8'\n\nservices:\n");
+        for (i, implementation) in self.implementations.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let context = match implementation.context.to_str() {
+                Some(".") => ".".to_string(),
+                _ => format!("./{}", implementation.context.display()),
+            };
+            // Infallible, writing to a String
+            let _ = write!(
+                out,
+                "  {name}:\n    build:\n      context: {context}\n      dockerfile: {dockerfile}\n    \
+                 ports:\n      - \"{port}:80\"\n    container_name: {container}\n",
+                name = implementation.name,
+                context = context,
+                dockerfile = implementation.dockerfile.display(),
+                port = implementation.port,
+                container = implementation.container_name(),
+            );
+        }
+        out
+    }
+
+    /// `benchmarks/scripts/implementations.json`, the manifest for the Node
+    /// scripts, with the URLs and container names filled in.
+    pub fn scripts_json(&self) -> String {
+        let implementations: Vec<ScriptsEntry> = self
+            .implementations
+            .iter()
+            .map(|implementation| ScriptsEntry {
+                implementation,
+                url: implementation.url(),
+                health_url: implementation.health_url(),
+                container: implementation.container_name(),
+            })
+            .collect();
+        let json =
+            serde_json::to_string_pretty(&implementations).expect("manifest serializes to JSON");
+        format!("{}\n", json)
+    }
+}
+
+impl Implementation {
+    /// `dir` on disk.
+    pub fn dir_path(&self) -> PathBuf {
+        repo_root().join(&self.dir)
+    }
+
+    /// `dist` on disk, for the entries that have one; every wasm app does.
+    pub fn dist_path(&self) -> Option<PathBuf> {
+        self.dist.as_ref().map(|dist| repo_root().join(dist))
+    }
+
+    /// Where its Docker service, or `cargo xtask serve`, answers.
+    pub fn url(&self) -> String {
+        format!("http://localhost:{}/", self.port)
+    }
+
+    pub fn health_url(&self) -> String {
+        format!("http://localhost:{}{}", self.port, self.health)
+    }
+
+    pub fn container_name(&self) -> String {
+        format!("frontend-benchmark-{}", self.name)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptsEntry<'a> {
+    #[serde(flatten)]
+    implementation: &'a Implementation,
+    url: String,
+    health_url: String,
+    container: String,
+}
+
+const GENERATED_YAML: &str = "\
+# Generated from implementations.toml by
+# `cargo run -p implementations -- generate`; edit that file instead.
+
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[[implementation]]
+name = "react"
+title = "React"
+language = "javascript"
+dir = "implementations/react"
+build = "npm run build"
+dist = "implementations/react/dist"
+port = 3001
+health = "/"
+context = "implementations/react"
+dockerfile = "Dockerfile"
+
+[[implementation]]
+name = "yew"
+title = "Yew"
+language = "rust"
+dir = "implementations/yew"
+build = "cargo xtask build yew"
+dist = "implementations/yew/dist"
+port = 3005
+health = "/"
+context = "."
+dockerfile = "implementations/yew/Dockerfile"
+package = "yew-todo"
+"#;
+
+    #[test]
+    fn generates_compose_services() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        let names: Vec<&str> = manifest.wasm_apps().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, ["yew"]);
+        assert!(manifest.compose().ends_with(
+            "services:\n  react:\n    build:\n      context: ./implementations/react\n      dockerfile: Dockerfile\n    \
+             ports:\n      - \"3001:80\"\n    container_name: frontend-benchmark-react\n\n  \
+             yew:\n    build:\n      context: .\n      dockerfile: implementations/yew/Dockerfile\n    \
+             ports:\n      - \"3005:80\"\n    container_name: frontend-benchmark-yew\n"
+        ));
+    }
+
+    #[test]
+    fn rejects_clashing_entries() {
+        let twice = MANIFEST.replace("\"yew\"", "\"react\"");
+        assert_eq!(
+            Manifest::parse(&twice).err().as_deref(),
+            Some("react is listed twice")
+        );
+        let port = MANIFEST.replace("3005", "3001");
+        assert!(Manifest::parse(&port).unwrap_err().contains("port 3001"));
+    }
+
+    #[test]
+    fn bundled_manifest_is_valid() {
+        let manifest = Manifest::load().unwrap();
+        assert!(manifest.wasm_apps().count() >= 3);
+    }
+}
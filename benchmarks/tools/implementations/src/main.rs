@@ -0,0 +1,81 @@
+//! Writes the files generated from `implementations.toml`, or with
+//! `--check`, fails when one of them is out of date.
+
+use implementations::{repo_root, Manifest};
+
+const USAGE: &str = "\
+Usage: implementations <COMMAND>
+
+Commands:
+  list                Print the name, port and health URL of every implementation
+  generate [--check]  Write docker-compose.yml and benchmarks/scripts/implementations.json;
+                      with --check, fail if either differs from the manifest instead
+
+Options:
+  -h, --help  Print this help";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let manifest = || Manifest::load().unwrap_or_else(|err| fail(&err));
+
+    match args.as_slice() {
+        ["-h" | "--help"] => println!("{}", USAGE),
+        ["list"] => {
+            for implementation in &manifest().implementations {
+                println!(
+                    "{:<18} {:>5}  {}",
+                    implementation.name,
+                    implementation.port,
+                    implementation.health_url()
+                );
+            }
+        }
+        ["generate"] => generate(&manifest(), false),
+        ["generate", "--check"] => generate(&manifest(), true),
+        [] => usage_error("missing command"),
+        _ => usage_error(&format!("unexpected arguments: {}", args.join(" "))),
+    }
+}
+
+fn generate(manifest: &Manifest, check: bool) {
+    let files = [
+        ("docker-compose.yml", manifest.compose()),
+        (
+            "benchmarks/scripts/implementations.json",
+            manifest.scripts_json(),
+        ),
+    ];
+
+    let mut stale = Vec::new();
+    for (file, contents) in files {
+        let path = repo_root().join(file);
+        if std::fs::read_to_string(&path).ok().as_deref() == Some(contents.as_str()) {
+            continue;
+        }
+        if check {
+            stale.push(file);
+        } else if let Err(err) = std::fs::write(&path, contents) {
+            fail(&format!("failed to write {}: {}", file, err));
+        } else {
+            println!("Wrote {}", file);
+        }
+    }
+    if !stale.is_empty() {
+        fail(&format!(
+            "out of date with implementations.toml: {}\n\
+             run `cargo run -p implementations -- generate`",
+            stale.join(", ")
+        ));
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
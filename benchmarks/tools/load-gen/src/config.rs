@@ -0,0 +1,147 @@
+//! Command-line options.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use url::Url;
+
+pub const USAGE: &str = "\
+Usage: load-gen [OPTIONS] <URL>
+
+Starts requests to URL at a constant rate, whether or not the earlier ones
+have finished, and times each from when it was due rather than when it was
+sent, so a server that stalls shows in the tail latencies instead of
+slowing the load down (coordinated omission).
+
+Options:
+  --rate <N>          Requests, or page loads, started per second [default: 100]
+  --duration <SECS>   How long to keep starting them [default: 30]
+  --concurrency <N>   Most in flight at once; due ones wait for a slot [default: 1000]
+  --timeout <SECS>    Give up on one this long after it was due [default: 10]
+  --page              Fetch the page with the .js and .wasm it links, timed as one page load
+  --http2             HTTP/2 without TLS (prior knowledge) instead of HTTP/1.1 keep-alive
+  --container <NAME>  Sample `docker stats` of this container during the run
+  --out <FILE>        JSON output, - for stdout [default: -]
+  -h, --help          Print this help";
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub url: Url,
+    /// Units started per second.
+    pub rate: f64,
+    pub duration: Duration,
+    pub concurrency: usize,
+    pub timeout: Duration,
+    /// Whether a unit is a page load rather than a single request.
+    pub page: bool,
+    pub http2: bool,
+    pub container: Option<String>,
+    pub out: PathBuf,
+}
+
+impl Config {
+    /// Parses the arguments after the program name; `Ok(None)` asks for
+    /// the usage text.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut url = None;
+        let mut rate = 100.0;
+        let mut duration = 30.0;
+        let mut concurrency = 1000;
+        let mut timeout = 10.0;
+        let mut page = false;
+        let mut http2 = false;
+        let mut container = None;
+        let mut out = PathBuf::from("-");
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value =
+                |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--rate" => rate = positive("--rate", &value("--rate")?)?,
+                "--duration" => duration = positive("--duration", &value("--duration")?)?,
+                "--concurrency" => {
+                    concurrency = value("--concurrency")?
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or("--concurrency needs a positive whole number")?;
+                }
+                "--timeout" => timeout = positive("--timeout", &value("--timeout")?)?,
+                "--page" => page = true,
+                "--http2" => http2 = true,
+                "--container" => container = Some(value("--container")?),
+                "--out" => out = PathBuf::from(value("--out")?),
+                flag if flag.starts_with('-') => return Err(format!("unknown option {}", flag)),
+                _ if url.is_some() => return Err(format!("unexpected argument {}", arg)),
+                _ => url = Some(arg),
+            }
+        }
+
+        let url = url.ok_or("missing <URL>")?;
+        let url = Url::parse(&url).map_err(|err| format!("invalid URL {}: {}", url, err))?;
+        if url.scheme() != "http" {
+            return Err(format!("{} is not an http:// URL", url));
+        }
+        Ok(Some(Self {
+            url,
+            rate,
+            duration: Duration::from_secs_f64(duration),
+            concurrency,
+            timeout: Duration::from_secs_f64(timeout),
+            page,
+            http2,
+            container,
+            out,
+        }))
+    }
+
+    /// How many units the run starts.
+    pub fn total(&self) -> u64 {
+        (self.rate * self.duration.as_secs_f64()).round().max(1.0) as u64
+    }
+}
+
+fn positive(name: &str, value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n > 0.0)
+        .ok_or_else(|| format!("{} needs a positive number, got {}", name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Config>, String> {
+        Config::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_options() {
+        let config = parse(&[
+            "--rate",
+            "250",
+            "--duration",
+            "2",
+            "--page",
+            "http://localhost:3005/",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.total(), 500);
+        assert!(config.page && !config.http2);
+        assert_eq!(config.concurrency, 1000);
+        assert_eq!(config.out, PathBuf::from("-"));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse(&["--rate", "0", "http://localhost/"]).is_err());
+        assert!(parse(&["https://localhost/"]).is_err());
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["--help"]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,192 @@
+//! CPU and memory of the server's container during a run, from one
+//! streaming `docker stats` process rather than one per sample.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+use serde::Serialize;
+
+/// The `containerStats` of a sample, as `stress-test.js` records them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerStats {
+    pub duration_seconds: u64,
+    pub samples: usize,
+    pub cpu: Cpu,
+    pub memory: Memory,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Cpu {
+    pub average: f64,
+    pub max: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Memory {
+    #[serde(rename = "averageMB")]
+    pub average_mb: f64,
+    #[serde(rename = "maxMB")]
+    pub max_mb: f64,
+    #[serde(rename = "averagePercent")]
+    pub average_percent: f64,
+    #[serde(rename = "maxPercent")]
+    pub max_percent: f64,
+}
+
+/// One line of `docker stats`: CPU %, memory in MiB and memory %.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Reading {
+    cpu: f64,
+    memory_mb: f64,
+    memory_percent: f64,
+}
+
+const FORMAT: &str = "{{.CPUPerc}}|{{.MemUsage}}|{{.MemPerc}}";
+
+/// A running `docker stats`, which refreshes about once a second.
+pub struct Sampler {
+    child: Child,
+    reader: JoinHandle<Vec<Reading>>,
+    started: Instant,
+}
+
+impl Sampler {
+    pub fn start(container: &str) -> Result<Self, String> {
+        let mut child = Command::new("docker")
+            .args(["stats", "--format", FORMAT, container])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("failed to run docker stats: {}", err))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let reader = std::thread::spawn(move || {
+            BufReader::new(stdout)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| parse_line(&line))
+                .collect()
+        });
+        Ok(Self {
+            child,
+            reader,
+            started: Instant::now(),
+        })
+    }
+
+    /// Stops `docker stats` and summarizes what it printed.
+    pub fn stop(mut self) -> ContainerStats {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let readings = self.reader.join().unwrap_or_default();
+        summarize(&readings, self.started.elapsed().as_secs())
+    }
+}
+
+fn summarize(readings: &[Reading], duration_seconds: u64) -> ContainerStats {
+    let values = |get: fn(&Reading) -> f64| readings.iter().map(get);
+    let average = |get: fn(&Reading) -> f64| {
+        if readings.is_empty() {
+            0.0
+        } else {
+            values(get).sum::<f64>() / readings.len() as f64
+        }
+    };
+    let max = |get: fn(&Reading) -> f64| values(get).fold(0.0, f64::max);
+    ContainerStats {
+        duration_seconds,
+        samples: readings.len(),
+        cpu: Cpu {
+            average: average(|r| r.cpu),
+            max: max(|r| r.cpu),
+        },
+        memory: Memory {
+            average_mb: average(|r| r.memory_mb),
+            max_mb: max(|r| r.memory_mb),
+            average_percent: average(|r| r.memory_percent),
+            max_percent: max(|r| r.memory_percent),
+        },
+    }
+}
+
+/// Parses `12.50%|45.5MiB / 7.775GiB|0.57%`, after the escape codes that
+/// clear the screen between refreshes.
+fn parse_line(line: &str) -> Option<Reading> {
+    let line = strip_escapes(line);
+    let mut parts = line.trim().split('|');
+    let percent = |part: Option<&str>| part?.trim().trim_end_matches('%').parse::<f64>().ok();
+    let cpu = percent(parts.next())?;
+    let usage = parts.next()?.split('/').next()?.trim();
+    let memory_percent = percent(parts.next())?;
+
+    let unit_at = usage.find(|c: char| c.is_ascii_alphabetic())?;
+    let value: f64 = usage[..unit_at].parse().ok()?;
+    let memory_mb = match usage[unit_at..].to_ascii_lowercase().chars().next()? {
+        'g' => value * 1024.0,
+        'm' => value,
+        'k' => value / 1024.0,
+        _ => value / (1024.0 * 1024.0),
+    };
+    Some(Reading {
+        cpu,
+        memory_mb,
+        memory_percent,
+    })
+}
+
+/// `line` without its ANSI escape sequences (`ESC [ ... letter`).
+fn strip_escapes(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            out.push(c);
+            continue;
+        }
+        for c in chars.by_ref() {
+            if c.is_ascii_alphabetic() {
+                break;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stats_lines() {
+        assert_eq!(
+            parse_line("\u{1b}[2J\u{1b}[H12.50%|45.5MiB / 7.775GiB|0.57%"),
+            Some(Reading {
+                cpu: 12.5,
+                memory_mb: 45.5,
+                memory_percent: 0.57
+            })
+        );
+        assert_eq!(
+            parse_line("0.00%|1.5GiB / 8GiB|18.75%").map(|r| r.memory_mb),
+            Some(1536.0)
+        );
+        assert_eq!(parse_line("CPU %|MEM USAGE / LIMIT|MEM %"), None);
+        assert_eq!(parse_line("--|-- / --|--"), None);
+    }
+
+    #[test]
+    fn summarizes_readings() {
+        let reading = |cpu, memory_mb| Reading {
+            cpu,
+            memory_mb,
+            memory_percent: 1.0,
+        };
+        let stats = summarize(&[reading(10.0, 40.0), reading(30.0, 50.0)], 2);
+        assert_eq!(stats.samples, 2);
+        assert_eq!((stats.cpu.average, stats.cpu.max), (20.0, 30.0));
+        assert_eq!((stats.memory.average_mb, stats.memory.max_mb), (45.0, 50.0));
+        assert_eq!(summarize(&[], 0), ContainerStats::default());
+    }
+}
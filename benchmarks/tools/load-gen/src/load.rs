@@ -0,0 +1,262 @@
+//! The open-loop run: units (requests or page loads) are started on a
+//! fixed schedule and each is timed from when it was due.
+//!
+//! A closed loop, like autocannon's, only sends the next request once the
+//! last one has answered, so while the server stalls it sends nothing and
+//! the stall shows up as one slow request instead of the many that would
+//! have queued behind it. Here a unit that waits for a free slot, or for a
+//! server that has stopped answering, is charged that wait. Tokio's timers
+//! tick in milliseconds, so a unit can also be sent up to a millisecond
+//! after it was due; the corrected latencies include that too.
+//!
+//! A unit that times out is charged the time to its timeout, or more if it
+//! was reported late, so a server that stops answering raises the corrected
+//! percentiles instead of dropping out of them. Units that fail with an
+//! error are only counted: a refused connection fails fast and would
+//! otherwise pull the percentiles down.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+use http_body_util::{BodyExt, Empty};
+use hyper::body::Bytes;
+use hyper::header::{ACCEPT_ENCODING, USER_AGENT};
+use hyper::{Request, Uri};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use url::Url;
+
+use crate::config::Config;
+use crate::page;
+use crate::stats;
+
+type HttpClient = Client<HttpConnector, Empty<Bytes>>;
+
+/// What a run recorded, over every unit that was started.
+pub struct Totals {
+    /// Latency from when each unit was due, in microseconds, of completed
+    /// and timed-out units.
+    pub corrected: Histogram<u64>,
+    /// Latency from when each unit was sent, as a closed loop measures it,
+    /// of completed units only.
+    pub uncorrected: Histogram<u64>,
+    /// Units that got an answer to every request.
+    pub completed: u64,
+    /// Completed units with a response that was not 2xx.
+    pub non2xx: u64,
+    /// Units that failed on a connection or protocol error.
+    pub errors: u64,
+    /// Units not finished within the timeout of when they were due.
+    pub timeouts: u64,
+    /// Responses by status code, subresources included.
+    pub status_codes: BTreeMap<u16, u64>,
+    /// Requests sent, subresources included.
+    pub requests: u64,
+    /// Body bytes received.
+    pub bytes: u64,
+    /// From the first unit being due to the last one finishing.
+    pub elapsed: Duration,
+}
+
+/// One finished unit.
+struct Done {
+    due: Instant,
+    sent: Instant,
+    finished: Instant,
+    outcome: Result<Fetched, Failure>,
+}
+
+struct Fetched {
+    statuses: Vec<u16>,
+    bytes: u64,
+}
+
+enum Failure {
+    Error(String),
+    Timeout,
+}
+
+pub async fn run(config: &Config) -> Totals {
+    // Without TCP_NODELAY, Nagle's algorithm and delayed ACKs add 40ms to
+    // requests that follow a small write
+    let mut connector = HttpConnector::new();
+    connector.set_nodelay(true);
+    let client: HttpClient = Client::builder(TokioExecutor::new())
+        .http2_only(config.http2)
+        .pool_max_idle_per_host(config.concurrency)
+        .build(connector);
+
+    let slots = Arc::new(Semaphore::new(config.concurrency));
+    let (done, mut finished) = mpsc::unbounded_channel();
+    let interval = Duration::from_secs_f64(1.0 / config.rate);
+    let timeout = config.timeout;
+    let start = Instant::now();
+
+    let collector = tokio::spawn(async move {
+        let mut totals = Totals {
+            corrected: stats::histogram(),
+            uncorrected: stats::histogram(),
+            completed: 0,
+            non2xx: 0,
+            errors: 0,
+            timeouts: 0,
+            status_codes: BTreeMap::new(),
+            requests: 0,
+            bytes: 0,
+            elapsed: Duration::ZERO,
+        };
+        let mut first_error = None;
+        while let Some(Done {
+            due,
+            sent,
+            finished,
+            outcome,
+        }) = finished.recv().await
+        {
+            totals.elapsed = totals.elapsed.max(finished - start);
+            match outcome {
+                Ok(fetched) => {
+                    totals.completed += 1;
+                    totals.corrected.saturating_record(micros(finished - due));
+                    totals
+                        .uncorrected
+                        .saturating_record(micros(finished - sent));
+                    if fetched.statuses.iter().any(|s| !(200..300).contains(s)) {
+                        totals.non2xx += 1;
+                    }
+                    for status in fetched.statuses {
+                        totals.requests += 1;
+                        *totals.status_codes.entry(status).or_default() += 1;
+                    }
+                    totals.bytes += fetched.bytes;
+                }
+                Err(Failure::Error(err)) => {
+                    totals.errors += 1;
+                    first_error.get_or_insert(err);
+                }
+                Err(Failure::Timeout) => {
+                    totals.timeouts += 1;
+                    totals
+                        .corrected
+                        .saturating_record(micros((finished - due).max(timeout)));
+                }
+            }
+        }
+        if let Some(err) = first_error {
+            eprintln!(
+                "warning: {} units failed, the first with: {}",
+                totals.errors, err
+            );
+        }
+        totals
+    });
+
+    for i in 0..config.total() {
+        let due = start + interval.mul_f64(i as f64);
+        tokio::time::sleep_until(due).await;
+
+        let (client, slots, done) = (client.clone(), slots.clone(), done.clone());
+        let (url, page) = (config.url.clone(), config.page);
+        let deadline = due + timeout;
+        tokio::spawn(async move {
+            let mut sent = due;
+            let unit = async {
+                let _slot = slots.acquire_owned().await.expect("slots are never closed");
+                sent = Instant::now();
+                fetch(&client, &url, page).await
+            };
+            let outcome = match tokio::time::timeout_at(deadline, unit).await {
+                Ok(outcome) => outcome.map_err(Failure::Error),
+                Err(_) => Err(Failure::Timeout),
+            };
+            let _ = done.send(Done {
+                due,
+                sent,
+                finished: Instant::now(),
+                outcome,
+            });
+        });
+    }
+    drop(done);
+    collector.await.expect("the collector does not panic")
+}
+
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros())
+        .unwrap_or(u64::MAX)
+        .max(1)
+}
+
+/// The unit: the document alone, or with `page` the document and then its
+/// code, fetched in parallel as a browser would.
+async fn fetch(client: &HttpClient, url: &Url, page: bool) -> Result<Fetched, String> {
+    // The document uncompressed, so its subresources can be read from it
+    let (status, body) = get(client, url, !page).await?;
+    let mut fetched = Fetched {
+        statuses: vec![status],
+        bytes: body.len() as u64,
+    };
+    if !page || !(200..300).contains(&status) {
+        return Ok(fetched);
+    }
+
+    let mut subresources = JoinSet::new();
+    for subresource in page::subresources(url, &String::from_utf8_lossy(&body)) {
+        let client = client.clone();
+        subresources.spawn(async move { get(&client, &subresource, true).await });
+    }
+    while let Some(result) = subresources.join_next().await {
+        let (status, body) = result.map_err(|err| err.to_string())??;
+        fetched.statuses.push(status);
+        fetched.bytes += body.len() as u64;
+    }
+    Ok(fetched)
+}
+
+/// GETs `url` and reads the whole body, compressed as a browser would ask
+/// for it when `compressed`.
+async fn get(client: &HttpClient, url: &Url, compressed: bool) -> Result<(u16, Bytes), String> {
+    let uri: Uri = url
+        .as_str()
+        .parse()
+        .map_err(|err| format!("{}: {}", url, err))?;
+    let mut request = Request::get(uri).header(USER_AGENT, "load-gen");
+    if compressed {
+        request = request.header(ACCEPT_ENCODING, "gzip, deflate, br");
+    }
+    let request = request
+        .body(Empty::new())
+        .map_err(|err| format!("{}: {}", url, err))?;
+
+    let response = client
+        .request(request)
+        .await
+        .map_err(|err| format!("{}: {}", url, error_chain(&err)))?;
+    let status = response.status().as_u16();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|err| format!("{}: {}", url, err))?
+        .to_bytes();
+    Ok((status, body))
+}
+
+/// An error with its sources, as the client's own message rarely says
+/// what went wrong.
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+    message
+}
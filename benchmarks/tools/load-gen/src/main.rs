@@ -0,0 +1,145 @@
+//! An open-loop HTTP load generator for `stress-test.js`: a constant rate
+//! of requests, or of whole page loads with the app's `.js` and `.wasm`,
+//! with latencies recorded in HDR histograms and corrected for coordinated
+//! omission (see `load.rs`).
+//!
+//! It prints one sample in the shape `stress-test.js` stores autocannon's,
+//! so the report reads either; `latencyUncorrected` is what a closed loop
+//! would have measured for the same run.
+
+mod config;
+mod docker;
+mod load;
+mod page;
+mod stats;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use config::{Config, USAGE};
+use docker::{ContainerStats, Sampler};
+use stats::{Latency, Rate};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Sample {
+    /// The most units in flight at once.
+    concurrency: usize,
+    duration: u64,
+    mode: &'static str,
+    rate: f64,
+    unit: &'static str,
+    protocol: &'static str,
+    requests: Rate,
+    latency: Latency,
+    latency_uncorrected: Latency,
+    /// The units each latency holds; units that failed with an error are
+    /// in neither (see `load.rs`).
+    latency_includes: &'static str,
+    latency_uncorrected_includes: &'static str,
+    throughput: Rate,
+    non2xx: u64,
+    errors: u64,
+    timeouts: u64,
+    status_codes: BTreeMap<String, u64>,
+    http_requests: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container_stats: Option<ContainerStats>,
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match Config::from_args(std::env::args().skip(1)) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let sampler = config
+        .container
+        .as_deref()
+        .map(|container| Sampler::start(container).unwrap_or_else(|err| fail(&err)));
+    let totals = load::run(&config).await;
+    let container_stats = sampler.map(Sampler::stop);
+
+    let seconds = totals.elapsed.as_secs_f64();
+    let sample = Sample {
+        concurrency: config.concurrency,
+        duration: config.duration.as_secs(),
+        mode: "open-loop",
+        rate: config.rate,
+        unit: if config.page { "page" } else { "request" },
+        protocol: if config.http2 { "h2c" } else { "http/1.1" },
+        requests: Rate::new(totals.completed, seconds),
+        latency: Latency::new(&totals.corrected),
+        latency_uncorrected: Latency::new(&totals.uncorrected),
+        latency_includes: "completed+timeouts",
+        latency_uncorrected_includes: "completed",
+        throughput: Rate::new(totals.bytes, seconds),
+        non2xx: totals.non2xx,
+        errors: totals.errors,
+        timeouts: totals.timeouts,
+        status_codes: totals
+            .status_codes
+            .iter()
+            .map(|(status, count)| (status.to_string(), *count))
+            .collect(),
+        http_requests: totals.requests,
+        container_stats,
+    };
+
+    print_summary(&config, &sample);
+    let json = serde_json::to_string_pretty(&sample).expect("sample serializes to JSON");
+    if config.out == Path::new("-") {
+        println!("{}", json);
+    } else if let Err(err) = std::fs::write(&config.out, json) {
+        fail(&format!(
+            "failed to write {}: {}",
+            config.out.display(),
+            err
+        ));
+    }
+}
+
+/// A summary on stderr, leaving stdout to the JSON.
+fn print_summary(config: &Config, sample: &Sample) {
+    eprintln!(
+        "{} at {}/s for {}s over {}: {} {}s completed ({:.0}/s), {} non-2xx, {} errors, {} timeouts",
+        config.url,
+        sample.rate,
+        config.duration.as_secs_f64(),
+        sample.protocol,
+        sample.requests.total,
+        sample.unit,
+        sample.requests.average,
+        sample.non2xx,
+        sample.errors,
+        sample.timeouts
+    );
+    for (kind, latency, includes) in [
+        ("latency", &sample.latency, sample.latency_includes),
+        (
+            "uncorrected",
+            &sample.latency_uncorrected,
+            sample.latency_uncorrected_includes,
+        ),
+    ] {
+        eprintln!(
+            "  {:<12} p50 {:.2}ms  p90 {:.2}ms  p99 {:.2}ms  p99.9 {:.2}ms  max {:.2}ms  ({})",
+            kind, latency.p50, latency.p90, latency.p99, latency.p99_9, latency.max, includes
+        );
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
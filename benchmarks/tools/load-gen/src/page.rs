@@ -0,0 +1,94 @@
+//! The subresources a page load fetches besides the document.
+
+use url::Url;
+
+/// Extensions of the subresources fetched with the page: the app's code.
+const CODE: &[&str] = &["js", "mjs", "wasm"];
+
+/// The `.js`, `.mjs` and `.wasm` files the page's `<script src>` and
+/// `<link href>` tags name, such as Trunk's and wasm-bindgen's module
+/// script and the preload of its `.wasm`, on the page's origin and each
+/// once.
+pub fn subresources(page: &Url, html: &str) -> Vec<Url> {
+    let mut found: Vec<Url> = Vec::new();
+    let lower = html.to_ascii_lowercase();
+    for (tag, attribute) in [("<script", "src"), ("<link", "href")] {
+        let mut rest = 0;
+        while let Some(at) = lower[rest..].find(tag) {
+            let start = rest + at + tag.len();
+            let end = lower[start..]
+                .find('>')
+                .map_or(lower.len(), |end| start + end);
+            rest = end;
+
+            let Some(value) = attribute_value(&html[start..end], attribute) else {
+                continue;
+            };
+            let Ok(url) = page.join(value) else {
+                continue;
+            };
+            let is_code = url
+                .path()
+                .rsplit_once('.')
+                .is_some_and(|(_, ext)| CODE.contains(&ext));
+            if is_code && url.origin() == page.origin() && !found.contains(&url) {
+                found.push(url);
+            }
+        }
+    }
+    found
+}
+
+/// The value of `name` among the attributes of a tag, quoted or not.
+fn attribute_value<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let lower = attributes.to_ascii_lowercase();
+    let mut rest = 0;
+    while let Some(at) = lower[rest..].find(name) {
+        let start = rest + at;
+        rest = start + name.len();
+        // A whole attribute name, not the end of another one (`data-src`)
+        let preceded = lower[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| !c.is_ascii_whitespace());
+        let value = lower[rest..].trim_start();
+        if preceded || !value.starts_with('=') {
+            continue;
+        }
+        let value_at = attributes.len() - value.len() + 1;
+        let value = attributes[value_at..].trim_start();
+        return match value.chars().next()? {
+            quote @ ('"' | '\'') => value[1..].split(quote).next(),
+            _ => value.split(|c: char| c.is_ascii_whitespace()).next(),
+        };
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_apps_code() {
+        let page = Url::parse("http://localhost:3005/todos/").unwrap();
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/todo-1a2b.css">
+            <link rel="modulepreload" href="/app-1f0e.js">
+            <link rel=preload href=app-1f0e_bg.wasm as=fetch type=application/wasm crossorigin>
+            <link rel="preload" href="http://cdn.example.com/lib.js">
+            <script type="module" data-src="/other.js" SRC='/app-1f0e.js'></script>
+        </head></html>"#;
+        let found: Vec<String> = subresources(&page, html)
+            .iter()
+            .map(Url::to_string)
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "http://localhost:3005/app-1f0e.js",
+                "http://localhost:3005/todos/app-1f0e_bg.wasm",
+            ]
+        );
+    }
+}
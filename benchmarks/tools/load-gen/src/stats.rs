@@ -0,0 +1,101 @@
+//! Summaries in the shape of autocannon's results, which
+//! `stress-test.js` stores as the `samples` of each framework.
+
+use hdrhistogram::Histogram;
+use serde::Serialize;
+
+/// Latency percentiles in milliseconds, from a histogram recorded in
+/// microseconds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Latency {
+    pub average: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    #[serde(rename = "p99_9")]
+    pub p99_9: f64,
+    pub total_count: u64,
+}
+
+impl Latency {
+    pub fn new(micros: &Histogram<u64>) -> Self {
+        if micros.is_empty() {
+            return Self::default();
+        }
+        let ms = |us: u64| us as f64 / 1000.0;
+        let mean = micros.mean() / 1000.0;
+        Self {
+            average: mean,
+            mean,
+            stddev: micros.stdev() / 1000.0,
+            min: ms(micros.min()),
+            max: ms(micros.max()),
+            p50: ms(micros.value_at_quantile(0.5)),
+            p90: ms(micros.value_at_quantile(0.9)),
+            p99: ms(micros.value_at_quantile(0.99)),
+            p99_9: ms(micros.value_at_quantile(0.999)),
+            total_count: micros.len(),
+        }
+    }
+}
+
+/// A count over the run, and its rate per second.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Rate {
+    pub average: f64,
+    pub mean: f64,
+    pub total: u64,
+}
+
+impl Rate {
+    pub fn new(total: u64, seconds: f64) -> Self {
+        let average = if seconds > 0.0 {
+            total as f64 / seconds
+        } else {
+            0.0
+        };
+        Self {
+            average,
+            mean: average,
+            total,
+        }
+    }
+}
+
+/// An empty histogram for latencies from 1µs to an hour, in microseconds,
+/// to 3 significant figures. Longer ones are recorded as an hour.
+pub fn histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, 3_600_000_000, 3).expect("the bounds are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_latencies_in_milliseconds() {
+        let mut micros = histogram();
+        for us in 1..=1000 {
+            micros.record(us * 1000).unwrap();
+        }
+        let latency = Latency::new(&micros);
+        assert_eq!(latency.total_count, 1000);
+        assert_eq!((latency.min, latency.max), (1.0, 1000.447));
+        assert_eq!((latency.p50, latency.p90), (500.223, 900.095));
+        assert_eq!(latency.p99_9, 999.423);
+        assert!((latency.mean - 500.5).abs() < 0.5);
+
+        assert_eq!(Latency::new(&histogram()), Latency::default());
+    }
+
+    #[test]
+    fn rates_per_second() {
+        assert_eq!(Rate::new(300, 2.0).average, 150.0);
+        assert_eq!(Rate::new(5, 0.0).average, 0.0);
+    }
+}
@@ -0,0 +1,136 @@
+//! Command-line options.
+
+use std::path::PathBuf;
+
+use axum::http::HeaderValue;
+
+pub const USAGE: &str = "\
+Usage: static-server [OPTIONS] <DIR>
+
+Serves DIR (e.g. an app's dist/), preferring precompressed .br and .gz
+files and falling back to DIR/index.html for unknown paths.
+
+Options:
+  --listen <ADDR>               Address to bind [env LISTEN_ADDR, default 127.0.0.1:8080]
+  --mount <PREFIX=DIR>          Also serve DIR under PREFIX, e.g. /shared=../../shared (repeatable)
+  --cache-control <VALUE>       Cache-Control of unhashed files [default: no-cache]
+  --hashed-cache-control <VALUE>
+                                Cache-Control of files with a content hash in their name
+                                [default: public, max-age=31536000, immutable]
+  --coop <VALUE>                Cross-Origin-Opener-Policy [default: same-origin]
+  --coep <VALUE>                Cross-Origin-Embedder-Policy [default: require-corp]
+  --csp <VALUE>                 Content-Security-Policy [default: none]
+  --no-cross-origin-isolation   Send neither COOP nor COEP
+  -h, --help                    Print this help";
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub root: PathBuf,
+    pub listen: String,
+    /// Extra directories as (URL prefix, directory).
+    pub mounts: Vec<(String, PathBuf)>,
+    pub cache_control: String,
+    pub hashed_cache_control: String,
+    pub coop: Option<String>,
+    pub coep: Option<String>,
+    pub csp: Option<String>,
+}
+
+impl Config {
+    /// Parses the arguments after the program name; `Ok(None)` asks for
+    /// the usage text.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut root = None;
+        let mut listen = std::env::var("LISTEN_ADDR").unwrap_or_else(|_| "127.0.0.1:8080".into());
+        let mut mounts = Vec::new();
+        let mut cache_control = "no-cache".to_string();
+        let mut hashed_cache_control = "public, max-age=31536000, immutable".to_string();
+        // Cross-origin isolation by default: it lifts the coarsening of
+        // `performance.now()` that would otherwise blur short measures.
+        let mut coop = Some("same-origin".to_string());
+        let mut coep = Some("require-corp".to_string());
+        let mut csp = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value =
+                |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--listen" => listen = value("--listen")?,
+                "--mount" => {
+                    let mount = value("--mount")?;
+                    let (prefix, dir) = mount
+                        .split_once('=')
+                        .filter(|(prefix, _)| prefix.len() > 1 && prefix.starts_with('/'))
+                        .ok_or_else(|| format!("--mount expects /PREFIX=DIR, got {}", mount))?;
+                    mounts.push((prefix.trim_end_matches('/').to_string(), PathBuf::from(dir)));
+                }
+                "--cache-control" => cache_control = value("--cache-control")?,
+                "--hashed-cache-control" => hashed_cache_control = value("--hashed-cache-control")?,
+                "--coop" => coop = Some(value("--coop")?),
+                "--coep" => coep = Some(value("--coep")?),
+                "--csp" => csp = Some(value("--csp")?),
+                "--no-cross-origin-isolation" => {
+                    coop = None;
+                    coep = None;
+                }
+                flag if flag.starts_with('-') => return Err(format!("unknown option {}", flag)),
+                _ if root.is_some() => return Err(format!("unexpected argument {}", arg)),
+                _ => root = Some(PathBuf::from(arg)),
+            }
+        }
+
+        for value in [&cache_control, &hashed_cache_control]
+            .into_iter()
+            .chain([&coop, &coep, &csp].into_iter().flatten())
+        {
+            HeaderValue::from_str(value)
+                .map_err(|_| format!("invalid header value {:?}", value))?;
+        }
+
+        let root = root.ok_or("missing <DIR>")?;
+        if !root.is_dir() {
+            return Err(format!("{} is not a directory", root.display()));
+        }
+        Ok(Some(Self {
+            root,
+            listen,
+            mounts,
+            cache_control,
+            hashed_cache_control,
+            coop,
+            coep,
+            csp,
+        }))
+    }
+}
+
+/// Whether the file name carries a content hash, as bundlers add to every
+/// output but `index.html`: Trunk's `app-1f0e3c9a2b7d4e65_bg.wasm`, Vite's
+/// `index-B2xk9QfA.js` or Angular's `main.3f9a1c2b4d5e6f70.js`.
+pub fn is_hashed(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.split(['-', '.', '_']).any(|part| {
+        part.len() >= 8
+            && part.chars().all(|c| c.is_ascii_alphanumeric())
+            && part.chars().any(|c| c.is_ascii_digit())
+            && part.chars().any(|c| c.is_ascii_alphabetic())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_bundler_hashes() {
+        assert!(is_hashed("/todo-yew-1f0e3c9a2b7d4e65_bg.wasm"));
+        assert!(is_hashed("/assets/index-B2xk9QfA.js"));
+        assert!(is_hashed("/main.3f9a1c2b4d5e6f70.js"));
+
+        assert!(!is_hashed("/index.html"));
+        assert!(!is_hashed("/dioxus_todo_bg.wasm"));
+        assert!(!is_hashed("/shared/styles/todo.css"));
+    }
+}
@@ -0,0 +1,133 @@
+//! A static file server for local benchmark runs, standing in for the
+//! `nginx:alpine` images without Docker.
+//!
+//! Unlike their `nginx.conf` it compresses nothing on the fly: it serves
+//! the `.br` or `.gz` file next to the requested one when the client
+//! accepts it, whatever the size, so every framework is measured with the
+//! same compression. It also sets Cache-Control, cross-origin isolation
+//! and CSP headers, and logs how long each request took.
+
+mod config;
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::{Request, State};
+use axum::http::header::{
+    HeaderName, HeaderValue, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH,
+    CONTENT_SECURITY_POLICY, VARY,
+};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::serve::ListenerExt;
+use axum::Router;
+use tower_http::services::{ServeDir, ServeFile};
+
+use config::{is_hashed, Config, USAGE};
+
+const CROSS_ORIGIN_OPENER_POLICY: HeaderName =
+    HeaderName::from_static("cross-origin-opener-policy");
+const CROSS_ORIGIN_EMBEDDER_POLICY: HeaderName =
+    HeaderName::from_static("cross-origin-embedder-policy");
+
+#[tokio::main]
+async fn main() {
+    let config = match Config::from_args(std::env::args().skip(1)) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(err) = serve(config).await {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn serve(config: Config) -> std::io::Result<()> {
+    let index = ServeFile::new(config.root.join("index.html"))
+        .precompressed_br()
+        .precompressed_gzip();
+    let mut app = Router::new();
+    for (prefix, dir) in &config.mounts {
+        app = app.nest_service(prefix, files(ServeDir::new(dir)));
+    }
+    let app = app
+        .fallback_service(files(ServeDir::new(&config.root)).fallback(index))
+        .layer(middleware::from_fn_with_state(
+            Arc::new(config.clone()),
+            headers,
+        ));
+
+    let listener = tokio::net::TcpListener::bind(&config.listen).await?;
+    println!(
+        "Serving {} on http://{}",
+        config.root.display(),
+        config.listen
+    );
+    // As nginx's `tcp_nodelay on`: otherwise a keep-alive response whose
+    // body follows its headers waits out the client's delayed ACK
+    let listener = listener.tap_io(|tcp| {
+        let _ = tcp.set_nodelay(true);
+    });
+    axum::serve(listener, app).await
+}
+
+fn files(dir: ServeDir) -> ServeDir {
+    dir.precompressed_br().precompressed_gzip()
+}
+
+/// Adds the configured headers and logs the request with the time until
+/// its response headers were ready.
+async fn headers(State(config): State<Arc<Config>>, request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    let mut response = next.run(request).await;
+    let elapsed = start.elapsed();
+
+    let cache_control = if response.status().is_success() && is_hashed(&path) {
+        &config.hashed_cache_control
+    } else {
+        &config.cache_control
+    };
+    let headers = response.headers_mut();
+    for (name, value) in [
+        (CACHE_CONTROL, Some(cache_control)),
+        (CROSS_ORIGIN_OPENER_POLICY, config.coop.as_ref()),
+        (CROSS_ORIGIN_EMBEDDER_POLICY, config.coep.as_ref()),
+        (CONTENT_SECURITY_POLICY, config.csp.as_ref()),
+    ] {
+        if let Some(value) = value {
+            // Checked when the options were parsed
+            headers.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+    }
+    // Any file may have a precompressed sibling
+    headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+            .unwrap_or("-")
+    };
+    println!(
+        "{} {} {} {} {} {:.2}ms",
+        method,
+        path,
+        response.status().as_u16(),
+        header(CONTENT_ENCODING),
+        header(CONTENT_LENGTH),
+        elapsed.as_secs_f64() * 1000.0
+    );
+    response
+}
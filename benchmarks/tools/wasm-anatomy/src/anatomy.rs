@@ -0,0 +1,334 @@
+//! Breakdown of one `.wasm` module: sections, functions and crates.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use wasmparser::{BinaryReaderError, KnownCustom, Name, Parser, Payload, TypeRef};
+
+/// Group of functions the name section does not cover.
+pub const UNNAMED: &str = "[unnamed]";
+/// Group of named functions without a crate path, such as `memcpy` or the
+/// `__wbindgen_*` glue.
+pub const NO_CRATE: &str = "[no crate]";
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Anatomy {
+    pub size: usize,
+    pub gzip_size: usize,
+    pub brotli_size: usize,
+    pub summary: Summary,
+    /// Every section in file order, with the size of its contents.
+    pub sections: Vec<Section>,
+    pub functions: usize,
+    pub named_functions: usize,
+    pub largest_functions: Vec<Function>,
+    /// Function body bytes per crate, largest first.
+    pub crates: Vec<CrateSize>,
+}
+
+/// Bytes by kind; the five add up to the file size.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Summary {
+    pub code: usize,
+    pub data: usize,
+    /// Custom sections other than `name`: `producers`, debug info...
+    pub custom: usize,
+    pub name: usize,
+    /// Types, imports, exports, tables and the other small sections, plus
+    /// the header and section framing.
+    pub other: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Section {
+    /// `code`, `data`... or `custom:<name>`.
+    pub id: String,
+    pub size: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Function {
+    pub index: u32,
+    pub name: String,
+    #[serde(rename = "crate")]
+    pub krate: String,
+    pub size: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CrateSize {
+    #[serde(rename = "crate")]
+    pub krate: String,
+    pub functions: usize,
+    pub size: usize,
+    /// Fraction of all function body bytes.
+    pub share: f64,
+}
+
+/// Parses `bytes` and keeps the `top` largest functions.
+pub fn analyze(bytes: &[u8], top: usize) -> Result<Anatomy, BinaryReaderError> {
+    let mut sections = Vec::new();
+    let mut summary = Summary::default();
+    let mut imported_functions = 0u32;
+    let mut bodies = Vec::new();
+    let mut names = HashMap::new();
+
+    for payload in Parser::new(0).parse_all(bytes) {
+        let payload = payload?;
+        let section = match &payload {
+            Payload::ImportSection(imports) => {
+                for import in imports.clone().into_imports() {
+                    if matches!(import?.ty, TypeRef::Func(_) | TypeRef::FuncExact(_)) {
+                        imported_functions += 1;
+                    }
+                }
+                None
+            }
+            Payload::CodeSectionEntry(body) => {
+                bodies.push(body.range().len());
+                None
+            }
+            Payload::CustomSection(custom) => {
+                if let KnownCustom::Name(subsections) = custom.as_known() {
+                    for subsection in subsections {
+                        if let Name::Function(functions) = subsection? {
+                            for naming in functions {
+                                let naming = naming?;
+                                names.insert(naming.index, naming.name);
+                            }
+                        }
+                    }
+                    summary.name += custom.range().len();
+                } else {
+                    summary.custom += custom.range().len();
+                }
+                Some((format!("custom:{}", custom.name()), custom.range().len()))
+            }
+            _ => None,
+        };
+        let section = section.or_else(|| {
+            let (id, range) = payload.as_section()?;
+            match section_name(id) {
+                "code" => summary.code += range.len(),
+                "data" => summary.data += range.len(),
+                _ => {}
+            }
+            Some((section_name(id).to_string(), range.len()))
+        });
+        if let Some((id, size)) = section {
+            sections.push(Section { id, size });
+        }
+    }
+    summary.other = bytes.len() - summary.code - summary.data - summary.custom - summary.name;
+
+    let mut functions: Vec<Function> = bodies
+        .iter()
+        .enumerate()
+        .map(|(i, &size)| {
+            let index = imported_functions + i as u32;
+            let (name, krate) = match names.get(&index) {
+                Some(symbol) => {
+                    let name = format!("{:#}", rustc_demangle::demangle(symbol));
+                    let krate = crate_of(&name).to_string();
+                    (name, krate)
+                }
+                None => (format!("func[{}]", index), UNNAMED.to_string()),
+            };
+            Function {
+                index,
+                name,
+                krate,
+                size,
+            }
+        })
+        .collect();
+    let named_functions = functions.iter().filter(|f| f.krate != UNNAMED).count();
+
+    let code_bytes: usize = bodies.iter().sum();
+    let mut crates: HashMap<&str, (usize, usize)> = HashMap::new();
+    for function in &functions {
+        let (count, size) = crates.entry(&function.krate).or_default();
+        *count += 1;
+        *size += function.size;
+    }
+    let mut crates: Vec<CrateSize> = crates
+        .into_iter()
+        .map(|(krate, (functions, size))| CrateSize {
+            krate: krate.to_string(),
+            functions,
+            size,
+            share: size as f64 / code_bytes.max(1) as f64,
+        })
+        .collect();
+    crates.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.krate.cmp(&b.krate)));
+
+    let function_count = functions.len();
+    functions.sort_by(|a, b| b.size.cmp(&a.size).then(a.index.cmp(&b.index)));
+    functions.truncate(top);
+
+    Ok(Anatomy {
+        size: bytes.len(),
+        gzip_size: gzip_size(bytes),
+        brotli_size: brotli_size(bytes),
+        summary,
+        sections,
+        functions: function_count,
+        named_functions,
+        largest_functions: functions,
+        crates,
+    })
+}
+
+/// The crate a demangled symbol comes from: the first segment of its path,
+/// or of the type in `<Type as Trait>::method`. `std`, `core` and `alloc`
+/// are reported together as `std`.
+///
+/// Generic code is attributed to the crate that defines it, so
+/// `core::ptr::drop_in_place<leptos::...>` counts as `std`.
+pub fn crate_of(symbol: &str) -> &str {
+    let mut path = symbol;
+    loop {
+        let trimmed = path
+            .trim_start_matches(['<', '&', '*', '('])
+            .trim_start_matches("mut ")
+            .trim_start_matches("const ")
+            .trim_start_matches("dyn ");
+        if trimmed == path {
+            break;
+        }
+        path = trimmed;
+    }
+
+    match path.split_once("::") {
+        Some(("std" | "core" | "alloc", _)) => "std",
+        Some((krate, _))
+            if !krate.is_empty()
+                && krate.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            krate
+        }
+        _ => NO_CRATE,
+    }
+}
+
+/// Section ids as the core spec numbers them.
+fn section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+/// Size at `gzip -9`, as nginx or a precompression step would serve it.
+fn gzip_size(bytes: &[u8]) -> usize {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(bytes)
+        .expect("writing to a Vec cannot fail");
+    encoder
+        .finish()
+        .expect("writing to a Vec cannot fail")
+        .len()
+}
+
+/// Size at brotli's highest quality.
+fn brotli_size(bytes: &[u8]) -> usize {
+    let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+    encoder
+        .write_all(bytes)
+        .expect("writing to a Vec cannot fail");
+    encoder.into_inner().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_symbols_to_crates() {
+        assert_eq!(
+            crate_of("yew::html::component::scope::Scope<C>::send_message"),
+            "yew"
+        );
+        assert_eq!(crate_of("<leptos::Foo as core::fmt::Debug>::fmt"), "leptos");
+        assert_eq!(
+            crate_of("<&mut serde_json::ser::Serializer<W>>::serialize_str"),
+            "serde_json"
+        );
+        assert_eq!(crate_of("alloc::raw_vec::finish_grow"), "std");
+        assert_eq!(
+            crate_of("core::ptr::drop_in_place<dioxus_core::VNode>"),
+            "std"
+        );
+        assert_eq!(crate_of("__wbindgen_malloc"), NO_CRATE);
+    }
+
+    /// A module with one imported and two defined functions, one of them
+    /// named.
+    fn module() -> Vec<u8> {
+        fn section(id: u8, contents: &[u8]) -> Vec<u8> {
+            let mut section = vec![id, contents.len() as u8];
+            section.extend_from_slice(contents);
+            section
+        }
+
+        let symbol = b"_ZN3yew4html6render17h0123456789abcdefE";
+        let mut names = vec![4, b'n', b'a', b'm', b'e'];
+        names.extend([1, symbol.len() as u8 + 3, 1, 1, symbol.len() as u8]);
+        names.extend_from_slice(symbol);
+
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        bytes.extend(section(1, &[1, 0x60, 0, 0]));
+        bytes.extend(section(2, &[1, 1, b'm', 1, b'f', 0, 0]));
+        bytes.extend(section(3, &[2, 0, 0]));
+        bytes.extend(section(10, &[2, 2, 0, 0x0b, 4, 0, 0x01, 0x01, 0x0b]));
+        bytes.extend(section(0, &names));
+        bytes
+    }
+
+    #[test]
+    fn splits_a_module_into_sections_and_functions() {
+        let bytes = module();
+        let anatomy = analyze(&bytes, 10).unwrap();
+
+        let ids: Vec<&str> = anatomy.sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["type", "import", "function", "code", "custom:name"]);
+        let summary = &anatomy.summary;
+        assert_eq!(summary.code, 9);
+        assert_eq!(
+            summary.code + summary.data + summary.custom + summary.name + summary.other,
+            bytes.len()
+        );
+
+        assert_eq!(anatomy.functions, 2);
+        assert_eq!(anatomy.named_functions, 1);
+        let largest = &anatomy.largest_functions[0];
+        assert_eq!((largest.index, largest.size), (2, 4));
+        assert_eq!(largest.krate, UNNAMED);
+        let named = &anatomy.largest_functions[1];
+        assert_eq!((named.index, named.name.as_str()), (1, "yew::html::render"));
+
+        let crates: Vec<(&str, usize)> = anatomy
+            .crates
+            .iter()
+            .map(|c| (c.krate.as_str(), c.size))
+            .collect();
+        assert_eq!(crates, [(UNNAMED, 4), ("yew", 2)]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Explains what a framework's `.wasm` is made of, where
+//! `bundle-size.js` only adds up file sizes.
+//!
+//! For every module it reports the size of each section, the largest
+//! functions, function bytes per crate (yew, leptos, dioxus, std, serde...)
+//! and the gzip and brotli sizes, and writes it all as JSON for the report.
+//! Functions and crates come from the `name` section, so build with it
+//! kept (Trunk's `data-keep-debug`) to see more than `[unnamed]`.
+
+mod anatomy;
+
+use std::path::{Path, PathBuf};
+
+use implementations::Manifest;
+use serde::Serialize;
+
+use anatomy::Anatomy;
+
+const USAGE: &str = "\
+Usage: wasm-anatomy [OPTIONS] [NAME=FILE.wasm ...]
+
+Without files, analyzes every .wasm in the dist/ of each Rust app in
+implementations.toml.
+
+Options:
+  --top <N>       Number of largest functions to list [default: 20]
+  --out <FILE>    JSON output, - for stdout [default: benchmarks/results/wasm-anatomy.json]
+  -h, --help      Print this help";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    framework: String,
+    file: PathBuf,
+    timestamp: u64,
+    #[serde(flatten)]
+    anatomy: Anatomy,
+}
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../..")
+}
+
+fn main() {
+    let mut top = 20;
+    let mut out = repo_root().join("benchmarks/results/wasm-anatomy.json");
+    let mut inputs = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--top" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => top = n,
+                None => usage_error("--top needs a number"),
+            },
+            "--out" => match args.next() {
+                Some(path) => out = PathBuf::from(path),
+                None => usage_error("--out needs a path"),
+            },
+            flag if flag.starts_with('-') => usage_error(&format!("unknown option {}", flag)),
+            input => match input.split_once('=') {
+                Some((name, file)) => inputs.push((name.to_string(), PathBuf::from(file))),
+                None => usage_error(&format!("expected NAME=FILE.wasm, got {}", input)),
+            },
+        }
+    }
+    if inputs.is_empty() {
+        let manifest = Manifest::load().unwrap_or_else(|err| fail(&err));
+        inputs = discover(&manifest);
+        if inputs.is_empty() {
+            eprintln!("No .wasm files found; build the Rust apps with `cargo xtask build` first.");
+            std::process::exit(1);
+        }
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let mut reports = Vec::new();
+    for (framework, file) in inputs {
+        let bytes = match std::fs::read(&file) {
+            Ok(bytes) => bytes,
+            Err(err) => fail(&format!("failed to read {}: {}", file.display(), err)),
+        };
+        let anatomy = match anatomy::analyze(&bytes, top) {
+            Ok(anatomy) => anatomy,
+            Err(err) => fail(&format!("failed to parse {}: {}", file.display(), err)),
+        };
+        reports.push(Report {
+            framework,
+            file,
+            timestamp,
+            anatomy,
+        });
+    }
+
+    let json = serde_json::to_string_pretty(&reports).expect("reports serialize to JSON");
+    if out == Path::new("-") {
+        println!("{}", json);
+        return;
+    }
+    if let Some(dir) = out.parent() {
+        if let Err(err) = std::fs::create_dir_all(dir) {
+            fail(&format!("failed to create {}: {}", dir.display(), err));
+        }
+    }
+    if let Err(err) = std::fs::write(&out, json) {
+        fail(&format!("failed to write {}: {}", out.display(), err));
+    }
+
+    for report in &reports {
+        print_summary(report);
+    }
+    println!("Results saved to {}", out.display());
+}
+
+/// Every `.wasm` directly in the `dist` of a Rust app in the manifest.
+fn discover(manifest: &Manifest) -> Vec<(String, PathBuf)> {
+    let mut found = Vec::new();
+    for app in manifest.wasm_apps() {
+        let Some(dist) = app.dist_path() else {
+            continue;
+        };
+        let Ok(entries) = std::fs::read_dir(&dist) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+        files.sort();
+        found.extend(files.into_iter().map(|file| (app.name.clone(), file)));
+    }
+    found
+}
+
+fn print_summary(report: &Report) {
+    let anatomy = &report.anatomy;
+    let summary = &anatomy.summary;
+    println!("{} ({})", report.framework, report.file.display());
+    println!(
+        "  {} total, {} gzip, {} brotli",
+        kib(anatomy.size),
+        kib(anatomy.gzip_size),
+        kib(anatomy.brotli_size)
+    );
+    println!(
+        "  code {}, data {}, custom {}, name {}, other {}",
+        kib(summary.code),
+        kib(summary.data),
+        kib(summary.custom),
+        kib(summary.name),
+        kib(summary.other)
+    );
+    println!(
+        "  {} functions, {} named",
+        anatomy.functions, anatomy.named_functions
+    );
+    for krate in anatomy.crates.iter().take(8) {
+        println!(
+            "  {:>5.1}%  {:<24} {:>10}  {} functions",
+            krate.share * 100.0,
+            krate.krate,
+            kib(krate.size),
+            krate.functions
+        );
+    }
+    println!();
+}
+
+fn kib(bytes: usize) -> String {
+    format!("{:.1} KiB", bytes as f64 / 1024.0)
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,115 @@
+//! Server functions of the fullstack build.
+//!
+//! The list is loaded on the server, and every change to it is stored in
+//! its SQLite file before it is applied in the browser, so a reload shows
+//! the list the user saw. Undo and redo are applied first and then save
+//! the list they leave. Only the filter, which lives in the URL, and the
+//! `window.__bench` operations stay client-side.
+
+use dioxus::logger::tracing::warn;
+use dioxus::prelude::*;
+use todo_core::perf::measure_server;
+use todo_core::{Action, Todo, TodoState};
+
+#[cfg(feature = "server")]
+use crate::db::{self, DB};
+
+/// Runs `query` on the connection of a blocking thread, so SQLite never
+/// holds up the async executor.
+#[cfg(feature = "server")]
+async fn with_db<T: Send + 'static>(
+    query: impl FnOnce(&rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+) -> Result<T, ServerFnError> {
+    tokio::task::spawn_blocking(move || DB.with(query))
+        .await
+        .map_err(ServerFnError::new)?
+        .map_err(ServerFnError::new)
+}
+
+#[server]
+pub async fn load_todos() -> Result<TodoState, ServerFnError> {
+    with_db(db::load).await
+}
+
+/// Adds a todo with an id of at least `min_id`, see [`db::add`].
+#[server]
+pub async fn add_todo(text: String, min_id: usize) -> Result<Option<Todo>, ServerFnError> {
+    with_db(move |conn| db::add(conn, &text, min_id)).await
+}
+
+#[server]
+pub async fn toggle_todo(id: usize) -> Result<(), ServerFnError> {
+    with_db(move |conn| db::toggle(conn, id)).await
+}
+
+#[server]
+pub async fn delete_todo(id: usize) -> Result<(), ServerFnError> {
+    with_db(move |conn| db::delete(conn, id)).await
+}
+
+#[server]
+pub async fn edit_todo(id: usize, text: String) -> Result<(), ServerFnError> {
+    with_db(move |conn| db::edit(conn, id, &text)).await
+}
+
+#[server]
+pub async fn toggle_all() -> Result<(), ServerFnError> {
+    with_db(db::toggle_all).await
+}
+
+#[server]
+pub async fn clear_completed() -> Result<(), ServerFnError> {
+    with_db(db::clear_completed).await
+}
+
+#[server]
+pub async fn save_todos(todos: Vec<Todo>) -> Result<(), ServerFnError> {
+    with_db(move |conn| db::save(conn, &todos)).await
+}
+
+/// Whether `action` goes through the server before it is applied.
+pub fn is_stored(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::Add(_)
+            | Action::Toggle(_)
+            | Action::Delete(_)
+            | Action::Edit(..)
+            | Action::ToggleAll
+            | Action::ClearCompleted
+    )
+}
+
+/// Stores `action` on the server, given the client's `next_id`. Returns
+/// the id an added todo takes, which is the one the server gave it, or
+/// `None` if the call failed and the list should stay as it was.
+pub async fn store(action: &Action, next_id: usize) -> Option<usize> {
+    let name = action.name();
+    let stored = measure_server(name, async {
+        match action {
+            Action::Add(text) => {
+                let added = add_todo(text.clone(), next_id).await?;
+                return Ok(added.map_or(next_id, |todo| todo.id));
+            }
+            Action::Toggle(id) => toggle_todo(*id).await?,
+            Action::Delete(id) => delete_todo(*id).await?,
+            Action::Edit(id, text) => edit_todo(*id, text.clone()).await?,
+            Action::ToggleAll => toggle_all().await?,
+            Action::ClearCompleted => clear_completed().await?,
+            _ => {}
+        }
+        Ok::<_, ServerFnError>(next_id)
+    })
+    .await;
+
+    stored
+        .map_err(|err| warn!("Could not store {}: {}", name, err))
+        .ok()
+}
+
+/// Saves the list that undo or redo (`name`) left.
+pub async fn save(name: &'static str, todos: Vec<Todo>) {
+    if let Err(err) = measure_server(name, save_todos(todos)).await {
+        warn!("Could not store {}: {}", name, err);
+    }
+}
@@ -0,0 +1,248 @@
+//! The SQLite file behind the fullstack build's server functions.
+//!
+//! Rows keep the list order in `position`, so new todos (which the reducer
+//! prepends) get the lowest position. Ids come from `AUTOINCREMENT`, which
+//! never reuses one, unless the client asks for a higher one (see [`add`]).
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use todo_core::seed::SeedConfig;
+use todo_core::{Todo, TodoState};
+
+thread_local! {
+    /// One connection per server thread, opened on first use.
+    pub static DB: Connection = open().expect("failed to open the todo database");
+}
+
+/// Opens `TODO_DB` (default `todos.sqlite3`), seeding it on first use with
+/// the list given as `name=value` server arguments.
+fn open() -> rusqlite::Result<Connection> {
+    let path = std::env::var("TODO_DB").unwrap_or_else(|_| "todos.sqlite3".into());
+    let mut conn = Connection::open(path)?;
+    init(&mut conn, || {
+        SeedConfig::from_args(std::env::args().skip(1)).generate()
+    })?;
+    Ok(conn)
+}
+
+/// Creates the schema if needed and inserts the todos of `seed` into a
+/// database that never held one.
+fn init(conn: &mut Connection, seed: impl FnOnce() -> TodoState) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "PRAGMA journal_mode = WAL;
+         PRAGMA busy_timeout = 5000;
+         CREATE TABLE IF NOT EXISTS todos (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             position INTEGER NOT NULL,
+             text TEXT NOT NULL,
+             completed INTEGER NOT NULL
+         );",
+    )?;
+
+    // Only a database that never held a todo is seeded, so deleting every
+    // todo survives a restart.
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    if last_id(&tx)?.is_none() {
+        insert_all(&tx, &seed().todos)?;
+    }
+    tx.commit()
+}
+
+/// Inserts `todos` with their ids, in list order.
+fn insert_all(conn: &Connection, todos: &[Todo]) -> rusqlite::Result<()> {
+    let mut insert =
+        conn.prepare("INSERT INTO todos (id, position, text, completed) VALUES (?1, ?2, ?3, ?4)")?;
+    for (position, todo) in todos.iter().enumerate() {
+        insert.execute(params![
+            todo.id as i64,
+            position as i64,
+            todo.text,
+            todo.completed
+        ])?;
+    }
+    Ok(())
+}
+
+/// The highest id ever handed out, or `None` for a fresh database.
+fn last_id(conn: &Connection) -> rusqlite::Result<Option<usize>> {
+    conn.query_row(
+        "SELECT seq FROM sqlite_sequence WHERE name = 'todos'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .map(|seq| seq.map(|seq| seq as usize))
+}
+
+pub fn load(conn: &Connection) -> rusqlite::Result<TodoState> {
+    let mut select = conn.prepare("SELECT id, text, completed FROM todos ORDER BY position")?;
+    let todos = select
+        .query_map([], |row| {
+            Ok(Todo {
+                id: row.get::<_, i64>(0)? as usize,
+                text: row.get(1)?,
+                completed: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut state = TodoState::seeded(0);
+    state.todos = todos;
+    state.next_id = last_id(conn)?.unwrap_or(0) + 1;
+    Ok(state)
+}
+
+/// Prepends a todo and returns it, ignoring text that is empty after
+/// trimming like [`todo_core::Action::Add`] does.
+///
+/// Its id is the next one `AUTOINCREMENT` would pick, or `min_id` if that
+/// is higher: the client passes its `next_id`, so the todo never takes an
+/// id the client already gave a todo that only exists in the browser.
+pub fn add(conn: &Connection, text: &str, min_id: usize) -> rusqlite::Result<Option<Todo>> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    conn.execute(
+        "INSERT INTO todos (id, position, text, completed)
+         VALUES (
+             MAX(?1, COALESCE((SELECT seq FROM sqlite_sequence WHERE name = 'todos'), 0) + 1),
+             (SELECT COALESCE(MIN(position), 0) - 1 FROM todos),
+             ?2,
+             0
+         )",
+        params![min_id as i64, text],
+    )?;
+    Ok(Some(Todo {
+        id: conn.last_insert_rowid() as usize,
+        text: text.to_string(),
+        completed: false,
+    }))
+}
+
+pub fn toggle(conn: &Connection, id: usize) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE todos SET completed = NOT completed WHERE id = ?1",
+        params![id as i64],
+    )?;
+    Ok(())
+}
+
+pub fn delete(conn: &Connection, id: usize) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM todos WHERE id = ?1", params![id as i64])?;
+    Ok(())
+}
+
+/// Replaces a todo's text, trimmed; empty text deletes it, like
+/// [`todo_core::Action::Edit`].
+pub fn edit(conn: &Connection, id: usize, text: &str) -> rusqlite::Result<()> {
+    let text = text.trim();
+    if text.is_empty() {
+        return delete(conn, id);
+    }
+    conn.execute(
+        "UPDATE todos SET text = ?2 WHERE id = ?1",
+        params![id as i64, text],
+    )?;
+    Ok(())
+}
+
+/// Completes every todo, or un-completes them all if they already are.
+pub fn toggle_all(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE todos SET completed = NOT (SELECT MIN(completed) FROM todos)",
+        [],
+    )?;
+    Ok(())
+}
+
+pub fn clear_completed(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM todos WHERE completed", [])?;
+    Ok(())
+}
+
+/// Replaces the stored list with `todos`, as undo and redo leave it.
+pub fn save(conn: &Connection, todos: &[Todo]) -> rusqlite::Result<()> {
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    tx.execute("DELETE FROM todos", [])?;
+    insert_all(&tx, todos)?;
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use todo_core::model::{self, Implementation};
+    use todo_core::{Action, Filter};
+
+    use super::*;
+
+    /// The server functions' store, plus the filter that stays client-side.
+    struct Store {
+        conn: Connection,
+        filter: Filter,
+    }
+
+    impl Implementation for Store {
+        fn apply(&mut self, action: Action) {
+            let stored = match action {
+                Action::Add(text) => add(&self.conn, &text, 0).map(drop),
+                Action::Toggle(id) => toggle(&self.conn, id),
+                Action::Delete(id) => delete(&self.conn, id),
+                Action::Edit(id, text) => edit(&self.conn, id, &text),
+                Action::ToggleAll => toggle_all(&self.conn),
+                Action::ClearCompleted => clear_completed(&self.conn),
+                Action::SetFilter(filter) => {
+                    self.filter = filter;
+                    Ok(())
+                }
+                other => unreachable!("{:?} is not stored", other),
+            };
+            stored.expect("failed to store the action");
+        }
+
+        fn state(&self) -> TodoState {
+            let mut state = load(&self.conn).expect("failed to load the todos");
+            state.filter = self.filter;
+            state
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn store_matches_model(initial in model::initial_state(), actions in model::actions()) {
+            let mut conn = Connection::open_in_memory().unwrap();
+            init(&mut conn, || initial.clone()).unwrap();
+            let mut store = Store { conn, filter: initial.filter };
+
+            model::check(&initial, &mut store, &actions)?;
+        }
+    }
+
+    #[test]
+    fn add_takes_an_id_above_the_clients() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init(&mut conn, || TodoState::seeded(3)).unwrap();
+
+        assert_eq!(add(&conn, "Stored", 0).unwrap().unwrap().id, 4);
+        assert_eq!(add(&conn, "Past local todos", 10).unwrap().unwrap().id, 10);
+        assert_eq!(add(&conn, "Next", 0).unwrap().unwrap().id, 11);
+        assert_eq!(add(&conn, "  ", 0).unwrap(), None);
+        assert_eq!(load(&conn).unwrap().next_id, 12);
+    }
+
+    #[test]
+    fn save_replaces_the_list() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init(&mut conn, || TodoState::seeded(3)).unwrap();
+
+        let mut state = TodoState::seeded(3);
+        state.apply(Action::Swap(0, 2));
+        state.apply(Action::Delete(2));
+        save(&conn, &state.todos).unwrap();
+        assert_eq!(load(&conn).unwrap().todos, state.todos);
+
+        // An emptied list is not seeded again on the next start
+        save(&conn, &[]).unwrap();
+        init(&mut conn, || TodoState::seeded(3)).unwrap();
+        assert!(load(&conn).unwrap().todos.is_empty());
+    }
+}
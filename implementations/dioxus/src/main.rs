@@ -1,79 +1,243 @@
 #![allow(non_snake_case, unused)]
+#[cfg(feature = "fullstack")]
+mod api;
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+mod db;
+mod route;
+#[cfg(not(target_arch = "wasm32"))]
+mod ssr;
+
+use std::rc::Rc;
+
+use dioxus::html::geometry::PixelsVector2D;
 use dioxus::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use dioxus::web::HashHistory;
+use futures_util::StreamExt;
+use route::{use_navigate_filter, use_route_filter};
+use todo_core::bench::{install_bench_api, BenchRequest};
+use todo_core::history::History;
+use todo_core::perf::UpdateMarks;
+#[cfg(not(target_arch = "wasm32"))]
+use todo_core::preview::Preview;
+use todo_core::seed::SeedConfig;
+use todo_core::viewport::{Viewport, VisibleRange};
+use todo_core::web::{listen_history_shortcuts, HistoryShortcut, ListMode, Persistence};
+use todo_core::{remaining_label, Action, Filter, TodoState};
 
-#[derive(Clone, PartialEq)]
-struct Todo {
-    id: usize,
-    text: String,
-    completed: bool,
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    let builder = dioxus::LaunchBuilder::new()
+        .with_cfg(dioxus::web::Config::new().history(Rc::new(HashHistory::new(false))));
+    #[cfg(feature = "fullstack")]
+    builder.launch(route::FullstackRoot);
+    #[cfg(not(feature = "fullstack"))]
+    builder.launch(route::App);
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum Filter {
-    All,
-    Active,
-    Completed,
+/// With `--features server`, serves the fullstack build; the database is
+/// seeded from `name=value` arguments the first time it is created.
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+fn main() {
+    dioxus::launch(route::FullstackRoot);
 }
 
+/// Natively, prints the markup of `ssr.rs` for the arguments, see
+/// `todo_core::preview`.
+#[cfg(all(not(feature = "server"), not(target_arch = "wasm32")))]
 fn main() {
-    dioxus::launch(App);
+    let seed = SeedConfig::from_args(std::env::args().skip(1));
+    let preview = Preview::from_args(std::env::args().skip(1));
+    println!("{}", ssr::render_to_string(seed, preview));
 }
 
 #[component]
-fn App() -> Element {
-    // Generate 100 initial todos
-    let initial_todos: Vec<Todo> = (1..=100)
-        .map(|i| Todo {
-            id: i,
-            text: format!("Todo item {}", i),
-            completed: i % 3 == 0,
-        })
-        .collect();
-
-    let mut todos = use_signal(|| initial_todos);
+fn TodoApp() -> Element {
+    let route_filter = use_route_filter();
+    let navigate_filter = use_navigate_filter();
+    // The fullstack build hydrates the server's markup, so it must start
+    // from the same list: the database replaces `?persist`, the list is
+    // never virtualized, and the route filter is applied after hydration
+    let fullstack = cfg!(feature = "fullstack");
+    let persistence = use_hook(|| if fullstack { Persistence::disabled() } else { Persistence::from_url() });
+    let list_mode = use_hook(ListMode::from_url);
+    // The fullstack root provides the stored list and the server renderer
+    // its seed; the browser reads the URL
+    let seed = use_hook(|| try_consume_context::<SeedConfig>().unwrap_or_else(SeedConfig::from_url));
+    // The rest of the starting point, set on the first render only
+    let mut stored_route = None;
+    let mut start = (None, None);
+    let mut state = use_signal(|| {
+        let viewport = Viewport::from_url().filter(|_| !fullstack);
+        let state = try_consume_context::<TodoState>().unwrap_or_else(|| {
+            let (state, route) = persistence.start(route_filter, || seed.generate());
+            stored_route = route;
+            state
+        });
+        // `ServerRoot` provides it, see `todo_core::preview`
+        #[cfg(not(target_arch = "wasm32"))]
+        let (state, viewport, edited) = try_consume_context::<Preview>()
+            .unwrap_or_default()
+            .start(state, viewport);
+        #[cfg(target_arch = "wasm32")]
+        let edited: Option<(usize, String)> = None;
+        start = (viewport, edited);
+        state
+    });
+    let (viewport, edited) = start;
+    // `Some` when the list is virtualized
+    let mut viewport = use_signal(|| viewport);
+    let is_virtual = viewport.peek().is_some();
+    let mut stored_route = use_signal(|| stored_route);
     let mut input_value = use_signal(String::new);
-    let mut filter = use_signal(|| Filter::All);
-    let mut next_id = use_signal(|| 101usize);
+    let (editing, edit_value) = edited.unzip();
+    let mut editing = use_signal(|| editing);
+    let mut edit_value = use_signal(|| edit_value.unwrap_or_default());
 
-    // Computed values
-    let remaining_count = use_memo(move || {
-        todos.read().iter().filter(|t| !t.completed).count()
+    let mut history = use_signal(History::default);
+    // Updates waiting for the render effect below to set their end marks
+    let mut marks = use_signal(UpdateMarks::default);
+
+    let mut apply = move |action: Action| {
+        let changes = state.peek().changes(&action);
+        update(state, history, marks, action.name(), changes, |history, state| {
+            history.apply(state, action)
+        });
+    };
+
+    // In the fullstack build a list change is applied once the server has
+    // stored it, taking the id the server gave an added todo.
+    let mut dispatch = move |action: Action| {
+        #[cfg(feature = "fullstack")]
+        if api::is_stored(&action) {
+            let next_id = state.peek().next_id;
+            spawn(async move {
+                if let Some(id) = api::store(&action, next_id).await {
+                    let changes = state.peek().changes(&action);
+                    update(state, history, marks, action.name(), changes, |history, state| {
+                        let next_id = std::mem::replace(&mut state.next_id, id);
+                        let changed = history.apply(state, action);
+                        state.next_id = state.next_id.max(next_id);
+                        changed
+                    });
+                }
+            });
+            return;
+        }
+        apply(action);
+    };
+
+    // Undo or redo one step, moving the URL along if it changed the filter
+    let mut travel = move |name: &'static str,
+                           can: fn(&History) -> bool,
+                           step: fn(&mut History, &mut TodoState) -> bool| {
+        let filter = state.peek().filter;
+        let changes = can(&history.peek());
+        let changed = update(state, history, marks, name, changes, step);
+        if changed && state.peek().filter != filter {
+            navigate_filter(state.peek().filter);
+        } else if changed {
+            #[cfg(feature = "fullstack")]
+            spawn(api::save(name, state.peek().todos.clone()));
+        }
+    };
+
+    // The window listener runs outside of Dioxus, so it only forwards
+    // shortcuts to this coroutine.
+    let shortcuts = use_coroutine(move |mut rx: UnboundedReceiver<HistoryShortcut>| async move {
+        while let Some(shortcut) = rx.next().await {
+            match shortcut {
+                HistoryShortcut::Undo => travel("undo", History::can_undo, History::undo),
+                HistoryShortcut::Redo => travel("redo", History::can_redo, History::redo),
+            }
+        }
+    });
+    use_hook(move || {
+        let tx = shortcuts.tx();
+        Rc::new(listen_history_shortcuts(move |shortcut| {
+            let _ = tx.unbounded_send(shortcut);
+        }))
+    });
+
+    // `window.__bench` calls are forwarded the same way.
+    let bench = use_coroutine(move |mut rx: UnboundedReceiver<BenchRequest>| async move {
+        while let Some(mut request) = rx.next().await {
+            if let Some(action) = request.take_action() {
+                dispatch(action);
+            }
+            request.resolve(&state.peek());
+        }
+    });
+    use_hook(move || {
+        let tx = bench.tx();
+        install_bench_api(move |request| {
+            let _ = tx.unbounded_send(request);
+        });
     });
 
-    let remaining_text = use_memo(move || {
-        let count = *remaining_count.read();
-        format!("{} {} remaining", count, if count == 1 { "item" } else { "items" })
+    // Follows the route. After undo/redo moved the route along the state
+    // already shows its filter, so there is nothing to dispatch; at startup
+    // a restored filter moves the route instead
+    use_effect(use_reactive((&route_filter,), move |(route_filter,)| {
+        if let Some(stored) = stored_route.take() {
+            navigate_filter(stored);
+        } else if state.peek().filter != route_filter {
+            dispatch(Action::SetFilter(route_filter));
+        }
+    }));
+
+    // Effects run after the render that the state change caused
+    use_effect(move || {
+        state.read();
+        marks.write().rendered();
+    });
+
+    use_effect(move || {
+        if persistence.is_enabled() {
+            persistence.save(&state.read());
+        }
     });
 
     // Computed values
-    let filtered_todos = use_memo(move || {
-        todos.read().iter().filter(|todo| match *filter.read() {
-            Filter::All => true,
-            Filter::Active => !todo.completed,
-            Filter::Completed => todo.completed,
-        })
-        .cloned()
-        .collect::<Vec<_>>()
+    let filter = use_memo(move || state.read().filter);
+
+    let remaining_text = use_memo(move || remaining_label(state.read().remaining_count()));
+
+    let completed_count = use_memo(move || state.read().completed_count());
+
+    let all_completed = use_memo(move || state.read().all_completed());
+
+    let filtered_todos = use_memo(move || state.read().filtered_todos());
+
+    let range = use_memo(move || {
+        let total = filtered_todos.read().len();
+        match *viewport.read() {
+            Some(viewport) => viewport.visible(total),
+            None => VisibleRange::all(total),
+        }
     });
+    // Rendering reads only these, so scrolling within the window is free
+    let window = use_memo(move || range.read().start..range.read().end);
+    let padding = use_memo(move || (range.read().padding_top, range.read().padding_bottom));
+
+    let selected = use_memo(move || state.read().selected);
 
     // Actions
     let mut add_todo = move || {
         let text = input_value.read().clone();
         if !text.trim().is_empty() {
-            let id = *next_id.read();
-            todos.write().insert(
-                0,
-                Todo {
-                    id,
-                    text: text.clone(),
-                    completed: false,
-                },
-            );
-            *next_id.write() = id + 1;
+            dispatch(Action::Add(text));
             *input_value.write() = String::new();
         }
     };
 
+    // `take` leaves nothing for the blur after Enter/Escape, see `Action::Edit`
+    let mut commit_edit = move || {
+        if let Some(id) = editing.take() {
+            dispatch(Action::Edit(id, edit_value.take()));
+        }
+    };
+
     rsx! {
         div { class: "todo-app",
             div { class: "todo-header",
@@ -106,19 +270,19 @@ fn App() -> Element {
             div { class: "todo-filters",
                 button {
                     class: if *filter.read() == Filter::All { "btn filter-btn active" } else { "btn filter-btn" },
-                    onclick: move |_| filter.set(Filter::All),
+                    onclick: move |_| navigate_filter(Filter::All),
                     "aria-label": "Show all todos",
                     "All"
                 }
                 button {
                     class: if *filter.read() == Filter::Active { "btn filter-btn active" } else { "btn filter-btn" },
-                    onclick: move |_| filter.set(Filter::Active),
+                    onclick: move |_| navigate_filter(Filter::Active),
                     "aria-label": "Show active todos",
                     "Active"
                 }
                 button {
                     class: if *filter.read() == Filter::Completed { "btn filter-btn active" } else { "btn filter-btn" },
-                    onclick: move |_| filter.set(Filter::Completed),
+                    onclick: move |_| navigate_filter(Filter::Completed),
                     "aria-label": "Show completed todos",
                     "Completed"
                 }
@@ -128,41 +292,108 @@ fn App() -> Element {
                 "{remaining_text.read()}"
             }
 
+            div { class: "todo-bulk-actions",
+                label { class: "toggle-all-label",
+                    input {
+                        r#type: "checkbox",
+                        class: "toggle-all",
+                        checked: *all_completed.read(),
+                        onchange: move |_| dispatch(Action::ToggleAll),
+                        "aria-label": "Toggle all todos"
+                    }
+                    "Mark all as complete"
+                }
+                button {
+                    class: "btn btn-clear-completed",
+                    disabled: *completed_count.read() == 0,
+                    onclick: move |_| dispatch(Action::ClearCompleted),
+                    "aria-label": "Clear completed todos",
+                    "Clear completed"
+                }
+            }
+
             if filtered_todos.read().is_empty() {
                 div { class: "empty-state",
                     div { class: "empty-state-icon", "📝" }
                     div { class: "empty-state-text", "No todos to display" }
                 }
             } else {
-                ul { class: "todo-list",
-                    for todo in filtered_todos.read().clone() {
-                        li { 
-                            key: "{todo.id}",
-                            class: if todo.completed { "todo-item completed" } else { "todo-item" },
+                ul {
+                    class: "todo-list",
+                    class: if is_virtual { "virtual" },
+                    onscroll: move |evt| {
+                        if !is_virtual {
+                            return;
+                        }
+                        if let Some(viewport) = viewport.write().as_mut() {
+                            viewport.scroll_top = evt.scroll_top() as i32;
+                            viewport.height = evt.client_height();
+                        }
+                    },
+                    // Fires for each new element, see `VisibleRange::scroll_top`
+                    onmounted: move |evt| async move {
+                        if is_virtual {
+                            let top = range.peek().scroll_top as f64;
+                            let _ = evt.scroll(PixelsVector2D::new(0.0, top), ScrollBehavior::Instant).await;
+                        }
+                    },
+                    if is_virtual {
+                        li { class: "todo-spacer", style: "height: {padding().0}px", "aria-hidden": "true" }
+                    }
+                    for (offset, todo) in filtered_todos.read()[window()].iter().cloned().enumerate() {
+                        li {
+                            key: "{list_mode.key(window.read().start + offset, todo.id)}",
+                            class: "todo-item",
+                            class: if todo.completed { "completed" },
+                            class: if *selected.read() == Some(todo.id) { "selected" },
                             input {
                                 r#type: "checkbox",
                                 class: "todo-checkbox",
                                 checked: todo.completed,
-                                onchange: move |_| {
-                                    let id = todo.id;
-                                    if let Some(t) = todos.write().iter_mut().find(|t| t.id == id) {
-                                        t.completed = !t.completed;
-                                    }
-                                },
+                                onchange: move |_| dispatch(Action::Toggle(todo.id)),
                                 "aria-label": "Toggle {todo.text}"
                             }
-                            span { class: "todo-text", "{todo.text}" }
+                            if *editing.read() == Some(todo.id) {
+                                input {
+                                    r#type: "text",
+                                    class: "todo-edit",
+                                    value: "{edit_value.read()}",
+                                    onmounted: move |evt| async move {
+                                        let _ = evt.set_focus(true).await;
+                                    },
+                                    oninput: move |evt| edit_value.set(evt.value()),
+                                    onkeydown: move |evt| match evt.key() {
+                                        Key::Enter => commit_edit(),
+                                        Key::Escape => editing.set(None),
+                                        _ => {}
+                                    },
+                                    onblur: move |_| commit_edit(),
+                                    "aria-label": "Edit {todo.text}"
+                                }
+                            } else {
+                                span {
+                                    class: "todo-text",
+                                    ondoubleclick: {
+                                        let text = todo.text.clone();
+                                        move |_| {
+                                            edit_value.set(text.clone());
+                                            editing.set(Some(todo.id));
+                                        }
+                                    },
+                                    "{todo.text}"
+                                }
+                            }
                             button {
                                 class: "btn btn-delete",
-                                onclick: move |_| {
-                                    let id = todo.id;
-                                    todos.write().retain(|t| t.id != id);
-                                },
+                                onclick: move |_| dispatch(Action::Delete(todo.id)),
                                 "aria-label": "Delete {todo.text}",
                                 "Delete"
                             }
                         }
                     }
+                    if is_virtual {
+                        li { class: "todo-spacer", style: "height: {padding().1}px", "aria-hidden": "true" }
+                    }
                 }
             }
 
@@ -172,3 +403,22 @@ fn App() -> Element {
         }
     }
 }
+
+/// Runs `step` on the state, bracketed by `todo:<name>:*` marks that the
+/// render effect ends. Returns whether the state changed.
+///
+/// `write` re-renders every reader even if nothing changed, so the caller
+/// says from a `peek` whether `step` `changes` anything, and a no-op only
+/// records its marks.
+fn update(
+    mut state: Signal<TodoState>,
+    mut history: Signal<History>,
+    mut marks: Signal<UpdateMarks>,
+    name: &'static str,
+    changes: bool,
+    step: impl FnOnce(&mut History, &mut TodoState) -> bool,
+) -> bool {
+    marks
+        .write()
+        .measure(name, || changes && step(&mut history.write(), &mut state.write()))
+}
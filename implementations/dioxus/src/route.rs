@@ -0,0 +1,99 @@
+use std::rc::Rc;
+
+use dioxus::history::{History, MemoryHistory};
+use dioxus::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use todo_core::preview::Preview;
+use todo_core::seed::SeedConfig;
+use todo_core::Filter;
+
+use crate::TodoApp;
+
+/// The active filter, mirrored in the URL hash (`#/`, `#/active`, `#/completed`).
+///
+/// `TodoApp` is the layout for every route so its state survives filter
+/// changes; it reads the filter from the route instead of rendering an outlet.
+#[derive(Routable, Clone, PartialEq, Debug)]
+#[rustfmt::skip]
+pub enum Route {
+    #[layout(TodoApp)]
+        #[route("/", FilterRoute)]
+        All {},
+        #[route("/active", FilterRoute)]
+        Active {},
+        #[route("/completed", FilterRoute)]
+        Completed {},
+}
+
+impl From<Route> for Filter {
+    fn from(route: Route) -> Self {
+        match route {
+            Route::All {} => Filter::All,
+            Route::Active {} => Filter::Active,
+            Route::Completed {} => Filter::Completed,
+        }
+    }
+}
+
+impl From<Filter> for Route {
+    fn from(filter: Filter) -> Self {
+        match filter {
+            Filter::All => Route::All {},
+            Filter::Active => Route::Active {},
+            Filter::Completed => Route::Completed {},
+        }
+    }
+}
+
+#[component]
+pub fn App() -> Element {
+    rsx! {
+        Router::<Route> {}
+    }
+}
+
+/// The app at `/` with an in-memory history, a fixed seed and a preview
+/// state, for rendering outside the browser.
+#[cfg(not(target_arch = "wasm32"))]
+#[component]
+pub fn ServerRoot(seed: SeedConfig, preview: Preview) -> Element {
+    use_context_provider(|| seed);
+    use_context_provider(|| preview);
+    use_context_provider(|| Rc::new(MemoryHistory::default()) as Rc<dyn History>);
+    rsx! {
+        App {}
+    }
+}
+
+/// The root of the fullstack build: loads the list from the server's
+/// database, while server rendering and again from the serialized result
+/// while hydrating, and provides it to `TodoApp`.
+#[cfg(feature = "fullstack")]
+#[component]
+pub fn FullstackRoot() -> Element {
+    let loaded = use_server_future(crate::api::load_todos)?;
+    // Always `Some` once `use_server_future` has returned
+    let state = loaded.read().clone().transpose()?.unwrap_or_default();
+    use_context_provider(|| state);
+    rsx! {
+        App {}
+    }
+}
+
+/// The filter selected by the current route.
+pub fn use_route_filter() -> Filter {
+    use_route::<Route>().into()
+}
+
+/// Returns a function that pushes the route of a filter onto the history stack.
+pub fn use_navigate_filter() -> impl Fn(Filter) + Copy {
+    let navigator = use_navigator();
+    move |filter| {
+        navigator.push(Route::from(filter));
+    }
+}
+
+#[component]
+fn FilterRoute() -> Element {
+    rsx! {}
+}
@@ -0,0 +1,28 @@
+//! Native rendering of the app to an HTML string with `dioxus-ssr`.
+//!
+//! Used by `cargo run` to print the markup and by `cargo test` to check it
+//! without a browser.
+
+use dioxus::prelude::*;
+use todo_core::preview::Preview;
+use todo_core::seed::SeedConfig;
+
+use crate::route::{ServerRoot, ServerRootProps};
+
+pub fn render_to_string(seed: SeedConfig, preview: Preview) -> String {
+    let mut dom = VirtualDom::new_with_props(ServerRoot, ServerRootProps { seed, preview });
+    dom.rebuild_in_place();
+    dioxus::ssr::render(&dom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_the_fixtures() {
+        for fixture in conformance::fixtures::all() {
+            fixture.check("Dioxus", &render_to_string(fixture.seed, Preview::default()));
+        }
+    }
+}
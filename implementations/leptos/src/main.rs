@@ -1,68 +1,171 @@
-use leptos::prelude::*;
+mod route;
+#[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+mod server;
+#[cfg(not(target_arch = "wasm32"))]
+mod ssr;
 
-#[derive(Clone, PartialEq)]
-struct Todo {
-    id: usize,
-    text: String,
-    completed: bool,
-}
+use std::collections::HashMap;
 
-#[derive(Clone, Copy, PartialEq)]
-enum Filter {
-    All,
-    Active,
-    Completed,
-}
+use leptos::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use leptos_router::components::Router;
+use route::{use_hash_filter, use_navigate_filter};
+use todo_core::bench::install_bench_api;
+use todo_core::history::History;
+use todo_core::perf::UpdateMarks;
+#[cfg(not(target_arch = "wasm32"))]
+use todo_core::preview::Preview;
+use todo_core::seed::SeedConfig;
+use todo_core::viewport::{Viewport, VisibleRange};
+use todo_core::web::{listen_history_shortcuts, HistoryShortcut, ListMode, Persistence};
+use todo_core::{remaining_label, Action, Filter, Todo, TodoState};
 
 #[component]
 fn App() -> impl IntoView {
-    // Generate 100 initial todos
-    let initial_todos: Vec<Todo> = (1..=100)
-        .map(|i| Todo {
-            id: i,
-            text: format!("Todo item {}", i),
-            completed: i % 3 == 0,
-        })
-        .collect();
+    // A hydrated page must start from exactly what the server rendered,
+    // which only knows the seed: stored todos and the virtualized list are
+    // CSR-only, and the hash filter is applied once hydration is done
+    let hydrate = cfg!(feature = "hydrate");
+    let persistence = if hydrate { Persistence::disabled() } else { Persistence::from_url() };
+    let list_mode = ListMode::from_url();
+    let list_ref = NodeRef::<leptos::html::Ul>::new();
+    let hash_filter = use_hash_filter();
+    let navigate_filter = use_navigate_filter();
 
-    let (todos, set_todos) = signal(initial_todos);
+    // The server renderer provides its seed; the browser reads the URL
+    let seed = use_context::<SeedConfig>().unwrap_or_else(SeedConfig::from_url);
+    let viewport = Viewport::from_url().filter(|_| !hydrate);
+    let (initial_state, stored_route) = if hydrate {
+        (seed.generate(), None)
+    } else {
+        persistence.start(hash_filter.get_untracked(), || seed.generate())
+    };
+    // A context from `ssr.rs`, see `todo_core::preview`
+    #[cfg(not(target_arch = "wasm32"))]
+    let (initial_state, viewport, edited) =
+        use_context::<Preview>().unwrap_or_default().start(initial_state, viewport);
+    #[cfg(target_arch = "wasm32")]
+    let edited: Option<(usize, String)> = None;
+    // `Some` when the list is virtualized
+    let viewport = RwSignal::new(viewport);
+    let is_virtual = viewport.with_untracked(Option::is_some);
+    let (editing, edit_value) = edited.unzip();
+    let (editing, set_editing) = signal(editing);
+    let (edit_value, set_edit_value) = signal(edit_value.unwrap_or_default());
+    let (state, set_state) = signal(initial_state);
     let (input_value, set_input_value) = signal(String::new());
-    let (filter, set_filter) = signal(Filter::All);
-    let (next_id, set_next_id) = signal(101usize);
+
+    let history = StoredValue::new(History::default());
+    let marks = StoredValue::new(UpdateMarks::default());
+    let rows = StoredValue::new(HashMap::<usize, Row>::new());
+    // Notified when an update may have changed which todos are visible
+    let list_changed = Trigger::new();
+
+    // Pushes an update into the list, touching no more rows than it changed
+    let refresh = move |changed: Changed| match changed {
+        Changed::Selection => {}
+        Changed::Row(id) => {
+            rows.with_value(|rows| state.with_untracked(|state| Row::sync_one(rows, list_mode, state, id)));
+        }
+        // A keyed row stays with its todo, so the others are untouched when one goes
+        Changed::Removed if list_mode == ListMode::Keyed => list_changed.notify(),
+        Changed::Removed | Changed::List => {
+            list_changed.notify();
+            rows.with_value(|rows| state.with_untracked(|state| Row::sync_all(rows, list_mode, state)));
+        }
+    };
+
+    let dispatch = move |action: Action| {
+        let name = action.name();
+        let changed = state.with_untracked(|state| Changed::of(&action, state.filter));
+        if update(set_state, history, marks, name, |history, state| history.apply(state, action)) {
+            refresh(changed);
+        }
+    };
+
+    // Undo or redo one step, moving the URL along if it changed the filter
+    let travel = move |name: &'static str, step: fn(&mut History, &mut TodoState) -> bool| {
+        let filter = state.with_untracked(|state| state.filter);
+        if update(set_state, history, marks, name, step) {
+            refresh(Changed::List);
+        }
+        let new_filter = state.with_untracked(|state| state.filter);
+        if new_filter != filter {
+            navigate_filter.run(new_filter);
+        }
+    };
+
+    // Owned by the component, so the listener is removed when it unmounts
+    StoredValue::new_local(listen_history_shortcuts(move |shortcut| match shortcut {
+        HistoryShortcut::Undo => travel("undo", History::undo),
+        HistoryShortcut::Redo => travel("redo", History::redo),
+    }));
+
+    install_bench_api(move |mut request| {
+        if let Some(action) = request.take_action() {
+            dispatch(action);
+        }
+        state.with_untracked(|state| request.resolve(state));
+    });
+
+    // Follows the hash. After undo/redo moved the URL along the state
+    // already shows its filter, so there is nothing to dispatch; at startup
+    // a restored filter moves the URL instead
+    Effect::new(move |started: Option<()>| {
+        let filter = hash_filter.get();
+        if let (None, Some(stored)) = (started, stored_route) {
+            navigate_filter.run(stored);
+        } else if state.with_untracked(|state| state.filter) != filter {
+            dispatch(Action::SetFilter(filter));
+        }
+    });
+
+    if persistence.is_enabled() {
+        Effect::new(move |_| state.with(|state| persistence.save(state)));
+    }
 
     // Computed values
-    let filtered_todos = move || {
-        todos.with(|todos| {
-            todos
-                .iter()
-                .filter(|todo| match filter.get() {
-                    Filter::All => true,
-                    Filter::Active => !todo.completed,
-                    Filter::Completed => todo.completed,
-                })
-                .cloned()
+    let filter = Memo::new(move |_| state.with(|state| state.filter));
+    // The row list is only rebuilt when the visible ids change; toggles and
+    // edits reach their row through its `Row` signals instead, without
+    // recomputing this. Rows are keyed by slot, which is the todo id or, in
+    // non-keyed mode, its position.
+    let visible_ids = Memo::new(move |_| {
+        list_changed.track();
+        state.with_untracked(|state| state.filtered().map(|t| t.id).collect::<Vec<_>>())
+    });
+    let range = Memo::new(move |_| {
+        let total = visible_ids.with(Vec::len);
+        match viewport.get() {
+            Some(viewport) => viewport.visible(total),
+            None => VisibleRange::all(total),
+        }
+    });
+    let window = Memo::new(move |_| range.with(|range| range.start..range.end));
+    let visible_todos = move || {
+        visible_ids.track();
+        let window = window.get();
+        state.with_untracked(|state| {
+            state
+                .filtered()
+                .enumerate()
+                .skip(window.start)
+                .take(window.len())
+                .map(|(index, todo)| (list_mode.key(index, todo.id), todo.clone()))
                 .collect::<Vec<_>>()
         })
     };
-
-    let remaining_count = move || todos.with(|todos| todos.iter().filter(|t| !t.completed).count());
+    let has_visible = Memo::new(move |_| visible_ids.with(|ids| !ids.is_empty()));
+    let remaining_count = move || state.with(|state| state.remaining_count());
+    let completed_count = move || state.with(|state| state.completed_count());
+    let all_completed = move || state.with(|state| state.all_completed());
+    let selected = Selector::new(move || state.with(|state| state.selected));
 
     // Actions
     let add_todo = move |_| {
         let text = input_value.get();
         if !text.trim().is_empty() {
-            let id = next_id.get();
-            set_todos.update(|todos| {
-                todos.insert(
-                    0,
-                    Todo {
-                        id,
-                        text: text.clone(),
-                        completed: false,
-                    },
-                );
-            });
-            set_next_id.set(id + 1);
+            dispatch(Action::Add(text));
             set_input_value.set(String::new());
         }
     };
@@ -74,21 +177,11 @@ fn App() -> impl IntoView {
         }
     };
 
-    let toggle_todo = move |id: usize| {
-        set_todos.update(|todos| {
-            if let Some(todo) = todos.iter_mut().find(|t| t.id == id) {
-                todo.completed = !todo.completed;
-            }
-        });
-    };
+    let toggle_todo = move |id: usize| dispatch(Action::Toggle(id));
+    let delete_todo = move |id: usize| dispatch(Action::Delete(id));
+    let set_filter = move |filter: Filter| navigate_filter.run(filter);
 
-    let delete_todo = move |id: usize| {
-        set_todos.update(|todos| {
-            todos.retain(|t| t.id != id);
-        });
-    };
-
-    view! {
+    let view = view! {
         <div class="todo-app">
             <div class="todo-header">
                 <h1>"Todo List"</h1>
@@ -117,21 +210,21 @@ fn App() -> impl IntoView {
             <div class="todo-filters">
                 <button
                     class=move || format!("btn filter-btn {}", if filter.get() == Filter::All { "active" } else { "" })
-                    on:click=move |_| set_filter.set(Filter::All)
+                    on:click=move |_| set_filter(Filter::All)
                     aria-label="Show all todos"
                 >
                     "All"
                 </button>
                 <button
                     class=move || format!("btn filter-btn {}", if filter.get() == Filter::Active { "active" } else { "" })
-                    on:click=move |_| set_filter.set(Filter::Active)
+                    on:click=move |_| set_filter(Filter::Active)
                     aria-label="Show active todos"
                 >
                     "Active"
                 </button>
                 <button
                     class=move || format!("btn filter-btn {}", if filter.get() == Filter::Completed { "active" } else { "" })
-                    on:click=move |_| set_filter.set(Filter::Completed)
+                    on:click=move |_| set_filter(Filter::Completed)
                     aria-label="Show completed todos"
                 >
                     "Completed"
@@ -139,12 +232,33 @@ fn App() -> impl IntoView {
             </div>
 
             <div class="todo-stats">
-                {move || remaining_count()} " " {move || if remaining_count() == 1 { "item" } else { "items" }} " remaining"
+                {move || remaining_label(remaining_count())}
+            </div>
+
+            <div class="todo-bulk-actions">
+                <label class="toggle-all-label">
+                    <input
+                        type="checkbox"
+                        class="toggle-all"
+                        prop:checked=all_completed
+                        on:change=move |_| dispatch(Action::ToggleAll)
+                        aria-label="Toggle all todos"
+                    />
+                    "Mark all as complete"
+                </label>
+                <button
+                    class="btn btn-clear-completed"
+                    disabled=move || completed_count() == 0
+                    on:click=move |_| dispatch(Action::ClearCompleted)
+                    aria-label="Clear completed todos"
+                >
+                    "Clear completed"
+                </button>
             </div>
 
             {move || {
-                let todos_list = filtered_todos();
-                if todos_list.is_empty() {
+                let selected = selected.clone();
+                if !has_visible.get() {
                     view! {
                         <div class="empty-state">
                             <div class="empty-state-icon">"📝"</div>
@@ -153,27 +267,101 @@ fn App() -> impl IntoView {
                     }.into_any()
                 } else {
                     view! {
-                        <ul class="todo-list">
+                        <ul
+                            class="todo-list"
+                            class:virtual=is_virtual
+                            node_ref=list_ref
+                            on:scroll=move |ev| {
+                                if !is_virtual {
+                                    return;
+                                }
+                                let list = event_target::<web_sys::Element>(&ev);
+                                viewport.update(|viewport| {
+                                    if let Some(viewport) = viewport {
+                                        viewport.scroll_top = list.scroll_top();
+                                        viewport.height = list.client_height();
+                                    }
+                                });
+                            }
+                        >
+                            {is_virtual.then(|| view! {
+                                <li
+                                    class="todo-spacer"
+                                    style:height=move || format!("{}px", range.get().padding_top)
+                                    aria-hidden="true"
+                                ></li>
+                            })}
                             <For
-                                each=move || filtered_todos()
-                                key=|todo| todo.id
-                                children=move |todo: Todo| {
-                                    let id = todo.id;
-                                    let completed = todo.completed;
+                                each=visible_todos
+                                key=|(slot, _)| *slot
+                                children=move |(slot, todo): (usize, Todo)| {
+                                    let Row { id, completed, text } = Row::mount(rows, slot, todo);
+                                    let is_editing = Memo::new(move |_| editing.get() == Some(id.get()));
+                                    let edit_ref = NodeRef::<leptos::html::Input>::new();
+                                    let selected = selected.clone();
+
+                                    // After Enter/Escape the shared `editing` no longer names
+                                    // this row when its blur runs, see `Action::Edit`
+                                    let commit_edit = move || {
+                                        if editing.get_untracked() == Some(id.get_untracked()) {
+                                            set_editing.set(None);
+                                            dispatch(Action::Edit(id.get_untracked(), edit_value.get_untracked()));
+                                        }
+                                    };
+
+                                    Effect::new(move |_| {
+                                        if let Some(input) = edit_ref.get() {
+                                            let _ = input.focus();
+                                        }
+                                    });
+
                                     view! {
-                                        <li class=format!("todo-item {}", if completed { "completed" } else { "" })>
+                                        <li
+                                            class="todo-item"
+                                            class:completed=move || completed.get()
+                                            class:selected=move || selected.selected(&Some(id.get()))
+                                        >
                                             <input
                                                 type="checkbox"
                                                 class="todo-checkbox"
-                                                prop:checked=completed
-                                                on:change=move |_| toggle_todo(id)
-                                                aria-label=format!("Toggle {}", todo.text)
+                                                prop:checked=move || completed.get()
+                                                on:change=move |_| toggle_todo(id.get_untracked())
+                                                aria-label=move || format!("Toggle {}", text.get())
                                             />
-                                            <span class="todo-text">{todo.text.clone()}</span>
+                                            {move || if is_editing.get() {
+                                                view! {
+                                                    <input
+                                                        type="text"
+                                                        class="todo-edit"
+                                                        node_ref=edit_ref
+                                                        prop:value=move || edit_value.get()
+                                                        on:input=move |ev| set_edit_value.set(event_target_value(&ev))
+                                                        on:keydown=move |ev: web_sys::KeyboardEvent| match ev.key().as_str() {
+                                                            "Enter" => commit_edit(),
+                                                            "Escape" => set_editing.set(None),
+                                                            _ => {}
+                                                        }
+                                                        on:blur=move |_| commit_edit()
+                                                        aria-label=move || format!("Edit {}", text.get())
+                                                    />
+                                                }.into_any()
+                                            } else {
+                                                view! {
+                                                    <span
+                                                        class="todo-text"
+                                                        on:dblclick=move |_| {
+                                                            set_edit_value.set(text.get_untracked());
+                                                            set_editing.set(Some(id.get_untracked()));
+                                                        }
+                                                    >
+                                                        {move || text.get()}
+                                                    </span>
+                                                }.into_any()
+                                            }}
                                             <button
                                                 class="btn btn-delete"
-                                                on:click=move |_| delete_todo(id)
-                                                aria-label=format!("Delete {}", todo.text)
+                                                on:click=move |_| delete_todo(id.get_untracked())
+                                                aria-label=move || format!("Delete {}", text.get())
                                             >
                                                 "Delete"
                                             </button>
@@ -181,6 +369,13 @@ fn App() -> impl IntoView {
                                     }
                                 }
                             />
+                            {is_virtual.then(|| view! {
+                                <li
+                                    class="todo-spacer"
+                                    style:height=move || format!("{}px", range.get().padding_bottom)
+                                    aria-hidden="true"
+                                ></li>
+                            })}
                         </ul>
                     }.into_any()
                 }
@@ -190,10 +385,167 @@ fn App() -> impl IntoView {
                 "Frontend Benchmark - Leptos Implementation"
             </div>
         </div>
+    };
+
+    // Reruns when `list_ref` gets a new element, see `VisibleRange::scroll_top`
+    Effect::new(move |_| {
+        if let (Some(list), true) = (list_ref.get(), is_virtual) {
+            list.set_scroll_top(range.get_untracked().scroll_top);
+        }
+    });
+
+    // Created after the view so it runs once the DOM has been updated.
+    Effect::new(move |_| {
+        state.track();
+        marks.update_value(UpdateMarks::rendered);
+    });
+
+    view
+}
+
+/// Signals for one mounted row, so a change re-renders only that row.
+///
+/// `id` only changes in non-keyed mode, where a row shows whichever todo
+/// is at its position.
+#[derive(Clone, Copy)]
+struct Row {
+    id: RwSignal<usize>,
+    completed: RwSignal<bool>,
+    text: RwSignal<String>,
+}
+
+impl Row {
+    /// Creates the row's signals and registers them under `slot` until the
+    /// row unmounts.
+    fn mount(rows: StoredValue<HashMap<usize, Row>>, slot: usize, todo: Todo) -> Self {
+        let row = Row {
+            id: RwSignal::new(todo.id),
+            completed: RwSignal::new(todo.completed),
+            text: RwSignal::new(todo.text),
+        };
+        rows.update_value(|rows| {
+            rows.insert(slot, row);
+        });
+        on_cleanup(move || {
+            rows.try_update_value(|rows| rows.remove(&slot));
+        });
+        row
+    }
+
+    /// Pushes the visible todos into the rows mounted for them.
+    fn sync_all(rows: &HashMap<usize, Row>, list_mode: ListMode, state: &TodoState) {
+        if rows.is_empty() {
+            return;
+        }
+        for (index, todo) in state.filtered().enumerate() {
+            if let Some(row) = rows.get(&list_mode.key(index, todo.id)) {
+                row.sync(todo);
+            }
+        }
+    }
+
+    /// Pushes todo `id` into its row, if it is visible and mounted.
+    fn sync_one(rows: &HashMap<usize, Row>, list_mode: ListMode, state: &TodoState, id: usize) {
+        if let Some((index, todo)) = state.filtered().enumerate().find(|(_, todo)| todo.id == id) {
+            if let Some(row) = rows.get(&list_mode.key(index, id)) {
+                row.sync(todo);
+            }
+        }
+    }
+
+    fn sync(&self, todo: &Todo) {
+        if self.id.get_untracked() != todo.id {
+            self.id.set(todo.id);
+        }
+        if self.completed.get_untracked() != todo.completed {
+            self.completed.set(todo.completed);
+        }
+        if self.text.with_untracked(|text| *text != todo.text) {
+            self.text.set(todo.text.clone());
+        }
+    }
+}
+
+/// How much of the list an update has to refresh.
+#[derive(Clone, Copy)]
+enum Changed {
+    /// Only which row is highlighted, which `selected` follows by itself.
+    Selection,
+    /// One todo's completion or text, and it stays visible.
+    Row(usize),
+    /// One todo left the visible list.
+    Removed,
+    /// Which todos are visible, their order, or many of them at once.
+    List,
+}
+
+impl Changed {
+    /// What `action` changes, if it changes anything, under `filter`.
+    fn of(action: &Action, filter: Filter) -> Self {
+        match action {
+            Action::Toggle(id) if filter == Filter::All => Changed::Row(*id),
+            Action::Edit(id, text) if !text.trim().is_empty() => Changed::Row(*id),
+            Action::Toggle(_) | Action::Delete(_) | Action::Edit(..) => Changed::Removed,
+            Action::Select(_) => Changed::Selection,
+            _ => Changed::List,
+        }
     }
 }
 
+/// Runs `step` on the state, bracketed by `todo:<name>:*` marks that the
+/// effect after the view ends. Returns whether the state changed.
+fn update(
+    set_state: WriteSignal<TodoState>,
+    history: StoredValue<History>,
+    marks: StoredValue<UpdateMarks>,
+    name: &'static str,
+    step: impl FnOnce(&mut History, &mut TodoState) -> bool,
+) -> bool {
+    let mut changed = false;
+    set_state.maybe_update(|state| {
+        changed = marks
+            .try_update_value(|marks| {
+                marks.measure(name, || {
+                    history
+                        .try_update_value(|history| step(history, state))
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false);
+        changed
+    });
+    changed
+}
+
+#[cfg(target_arch = "wasm32")]
 fn main() {
     console_error_panic_hook::set_once();
-    mount_to_body(|| view! { <App /> });
+    let app = || {
+        view! {
+            <Router>
+                <App />
+            </Router>
+        }
+    };
+    // The hydrate build takes over the markup streamed by `server.rs`
+    #[cfg(feature = "hydrate")]
+    hydrate_body(app);
+    #[cfg(not(feature = "hydrate"))]
+    mount_to_body(app);
+}
+
+/// With `--features ssr`, serves the SSR + hydration build.
+#[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    server::serve().await
+}
+
+/// Natively, prints the markup of `ssr.rs` for the arguments, see
+/// `todo_core::preview`.
+#[cfg(all(not(feature = "ssr"), not(target_arch = "wasm32")))]
+fn main() {
+    let seed = SeedConfig::from_args(std::env::args().skip(1));
+    let preview = Preview::from_args(std::env::args().skip(1));
+    println!("{}", ssr::render_to_string(seed, preview));
 }
@@ -0,0 +1,30 @@
+use leptos::prelude::*;
+use leptos_router::hooks::{use_location, use_navigate};
+use leptos_router::NavigateOptions;
+use todo_core::Filter;
+
+/// The filter selected by the URL hash (`#/`, `#/active`, `#/completed`).
+///
+/// leptos_router has no hash-based router, so this reads the hash from the
+/// router's location, which follows both navigation and back/forward.
+/// Unknown hashes fall back to showing all todos.
+pub fn use_hash_filter() -> Memo<Filter> {
+    let location = use_location();
+    Memo::new(move |_| {
+        location
+            .hash
+            .with(|hash| Filter::from_path(hash.trim_start_matches('#')).unwrap_or_default())
+    })
+}
+
+/// Navigates to the hash route of a filter, keeping the path and query.
+pub fn use_navigate_filter() -> Callback<Filter> {
+    let location = use_location();
+    let navigate = use_navigate();
+    Callback::new(move |filter: Filter| {
+        let search = location.search.get_untracked();
+        let query = if search.is_empty() { String::new() } else { format!("?{}", search) };
+        let url = format!("{}{}#{}", location.pathname.get_untracked(), query, filter.path());
+        navigate(&url, NavigateOptions::default());
+    })
+}
@@ -0,0 +1,49 @@
+//! The axum server of the SSR + hydration build (`--features ssr`), see
+//! `todo_core::ssr`; each page streams the seeded list in order, followed
+//! by the hydration data.
+
+use std::sync::Arc;
+
+use any_spawner::Executor;
+use futures::stream::{self, Stream, StreamExt};
+use hydration_context::SsrSharedContext;
+use leptos::prelude::*;
+use leptos_router::components::Router;
+use leptos_router::location::RequestUrl;
+use todo_core::seed::SeedConfig;
+use todo_core::ssr;
+
+use crate::App;
+
+pub async fn serve() -> std::io::Result<()> {
+    // `Selector` spawns a task while rendering, so something has to run it
+    let _ = Executor::init_futures_executor();
+    ssr::serve("Leptos", render_stream).await
+}
+
+/// The app's markup in document order, then the `<script>` with the
+/// resources and errors the client reads back while hydrating.
+fn render_stream(seed: SeedConfig, url: String) -> impl Stream<Item = String> + Send {
+    let owner = Owner::new_root(Some(Arc::new(SsrSharedContext::new())));
+    let (html, data) = owner.with(|| {
+        provide_context(seed);
+        provide_context(RequestUrl::new(&url));
+        let html = view! {
+            <Router>
+                <App />
+            </Router>
+        }
+        .to_html_stream_in_order();
+        let data = Owner::current_shared_context()
+            .and_then(|context| context.pending_data())
+            .unwrap_or_else(|| Box::pin(stream::empty()));
+        (html, data)
+    });
+
+    html.chain(data.map(|chunk| format!("<script>{}</script>", chunk)))
+        // The owner holds the app's reactive state until the last chunk is out
+        .chain(stream::once(async move {
+            drop(owner);
+            String::new()
+        }))
+}
@@ -0,0 +1,44 @@
+//! Native rendering of the app to an HTML string with Leptos's `ssr` mode.
+//!
+//! Used by `cargo run` to print the markup and by `cargo test` to check it
+//! without a browser.
+
+use any_spawner::Executor;
+use leptos::prelude::*;
+use leptos_router::components::Router;
+use leptos_router::location::RequestUrl;
+use todo_core::preview::Preview;
+use todo_core::seed::SeedConfig;
+
+use crate::App;
+
+// The `ssr` server streams instead, see `server.rs`
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+pub fn render_to_string(seed: SeedConfig, preview: Preview) -> String {
+    // `Selector` spawns a task, so something has to be able to run it; an
+    // executor may already be set by an earlier call.
+    let _ = Executor::init_futures_executor();
+    Owner::new().with(|| {
+        provide_context(seed);
+        provide_context(preview);
+        provide_context(RequestUrl::new("/"));
+        view! {
+            <Router>
+                <App />
+            </Router>
+        }
+        .to_html()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_the_fixtures() {
+        for fixture in conformance::fixtures::all() {
+            fixture.check("Leptos", &render_to_string(fixture.seed, Preview::default()));
+        }
+    }
+}
@@ -1,19 +1,24 @@
-use yew::prelude::*;
-use web_sys::HtmlInputElement;
-
-#[derive(Clone, PartialEq)]
-struct Todo {
-    id: usize,
-    text: String,
-    completed: bool,
-}
+mod route;
+#[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+mod server;
+#[cfg(not(target_arch = "wasm32"))]
+mod ssr;
 
-#[derive(Clone, PartialEq)]
-pub enum Filter {
-    All,
-    Active,
-    Completed,
-}
+#[cfg(all(target_arch = "wasm32", not(feature = "hydrate")))]
+use route::Root;
+use route::Route;
+use todo_core::bench::{install_bench_api, BenchRequest};
+use todo_core::history::History;
+use todo_core::perf::UpdateMarks;
+#[cfg(not(target_arch = "wasm32"))]
+use todo_core::preview::Preview;
+use todo_core::seed::SeedConfig;
+use todo_core::viewport::{Viewport, VisibleRange};
+use todo_core::web::{listen_history_shortcuts, HistoryShortcut, ListMode, Persistence, ShortcutListener};
+use todo_core::{remaining_label, Action, Filter, Todo, TodoState};
+use yew::prelude::*;
+use yew_router::prelude::*;
+use web_sys::{Element, HtmlInputElement};
 
 pub enum Msg {
     AddTodo,
@@ -21,93 +26,245 @@ pub enum Msg {
     DeleteTodo(usize),
     UpdateInput(String),
     SetFilter(Filter),
+    RouteChanged(Option<Route>),
+    ToggleAll,
+    ClearCompleted,
+    Undo,
+    Redo,
+    Bench(BenchRequest),
+    Scrolled { scroll_top: i32, height: i32 },
+    StartEdit(usize),
+    UpdateEdit(String),
+    CommitEdit,
+    CancelEdit,
 }
 
 pub struct App {
-    todos: Vec<Todo>,
+    state: TodoState,
+    history: History,
+    marks: UpdateMarks,
+    persistence: Persistence,
+    list_mode: ListMode,
+    /// `Some` when the list is virtualized.
+    viewport: Option<Viewport>,
+    list_ref: NodeRef,
+    list_mounted: bool,
     input_value: String,
-    filter: Filter,
-    next_id: usize,
+    editing: Option<usize>,
+    edit_value: String,
+    edit_input: NodeRef,
+    _location_listener: Option<LocationHandle>,
+    _shortcuts: ShortcutListener,
+}
+
+impl App {
+    fn dispatch(&mut self, action: Action) -> bool {
+        let Self { state, history, .. } = self;
+        let changed = self.marks.measure(action.name(), || history.apply(state, action));
+        if changed {
+            self.persistence.save(&self.state);
+        }
+        changed
+    }
+
+    /// Runs an undo or redo step and moves the URL along if it changed the filter.
+    fn travel(
+        &mut self,
+        ctx: &Context<Self>,
+        name: &'static str,
+        step: fn(&mut History, &mut TodoState) -> bool,
+    ) -> bool {
+        let filter = self.state.filter;
+        let Self { state, history, .. } = self;
+        if !self.marks.measure(name, || step(history, state)) {
+            return false;
+        }
+        self.persistence.save(&self.state);
+        if self.state.filter != filter {
+            if let Some(navigator) = ctx.link().navigator() {
+                navigator.push(&Route::from(self.state.filter));
+            }
+        }
+        true
+    }
 }
 
 impl Component for App {
     type Message = Msg;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        // Generate 100 initial todos
-        let todos: Vec<Todo> = (1..=100)
-            .map(|i| Todo {
-                id: i,
-                text: format!("Todo item {}", i),
-                completed: i % 3 == 0,
+    fn create(ctx: &Context<Self>) -> Self {
+        // A hydrated page must start from exactly what the server rendered,
+        // which only knows the seed: stored todos and the virtualized list
+        // are CSR-only, and the hash filter is applied after hydration
+        let hydrate = cfg!(feature = "hydrate");
+        let persistence = if hydrate { Persistence::disabled() } else { Persistence::from_url() };
+        // The server renderer passes its seed down; the browser reads the URL
+        let seed = ctx
+            .link()
+            .context::<SeedConfig>(Callback::noop())
+            .map_or_else(SeedConfig::from_url, |(seed, _)| seed);
+        let viewport = Viewport::from_url().filter(|_| !hydrate);
+        let (state, stored_route) = if hydrate {
+            (seed.generate(), None)
+        } else {
+            persistence.start(route_filter(ctx.link().route()), || seed.generate())
+        };
+        if let (Some(filter), Some(navigator)) = (stored_route, ctx.link().navigator()) {
+            navigator.push(&Route::from(filter));
+        }
+        // `ssr.rs` provides it above `ServerRoot`, see `todo_core::preview`
+        #[cfg(not(target_arch = "wasm32"))]
+        let (state, viewport, edited) = ctx
+            .link()
+            .context::<Preview>(Callback::noop())
+            .map_or_else(Preview::default, |(preview, _)| preview)
+            .start(state, viewport);
+        #[cfg(target_arch = "wasm32")]
+        let edited: Option<(usize, String)> = None;
+        let (editing, edit_value) = edited.unzip();
+
+        let location_listener = ctx.link().add_location_listener(
+            ctx.link()
+                .callback(|location: Location| Msg::RouteChanged(Route::recognize(location.path()))),
+        );
+
+        let link = ctx.link().clone();
+        install_bench_api(move |request| link.send_message(Msg::Bench(request)));
+
+        let link = ctx.link().clone();
+        let shortcuts = listen_history_shortcuts(move |shortcut| {
+            link.send_message(match shortcut {
+                HistoryShortcut::Undo => Msg::Undo,
+                HistoryShortcut::Redo => Msg::Redo,
             })
-            .collect();
+        });
 
         Self {
-            todos,
+            state,
+            history: History::default(),
+            marks: UpdateMarks::default(),
+            persistence,
+            list_mode: ListMode::from_url(),
+            viewport,
+            list_ref: NodeRef::default(),
+            list_mounted: false,
             input_value: String::new(),
-            filter: Filter::All,
-            next_id: 101,
+            editing,
+            edit_value: edit_value.unwrap_or_default(),
+            edit_input: NodeRef::default(),
+            _location_listener: location_listener,
+            _shortcuts: shortcuts,
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::AddTodo => {
-                if !self.input_value.trim().is_empty() {
-                    self.todos.insert(
-                        0,
-                        Todo {
-                            id: self.next_id,
-                            text: self.input_value.clone(),
-                            completed: false,
-                        },
-                    );
-                    self.next_id += 1;
+                let changed = self.dispatch(Action::Add(self.input_value.clone()));
+                if changed {
                     self.input_value.clear();
-                    true
-                } else {
-                    false
-                }
-            }
-            Msg::ToggleTodo(id) => {
-                if let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) {
-                    todo.completed = !todo.completed;
-                    true
-                } else {
-                    false
                 }
+                changed
             }
-            Msg::DeleteTodo(id) => {
-                self.todos.retain(|t| t.id != id);
-                true
-            }
+            Msg::ToggleTodo(id) => self.dispatch(Action::Toggle(id)),
+            Msg::DeleteTodo(id) => self.dispatch(Action::Delete(id)),
             Msg::UpdateInput(value) => {
                 self.input_value = value;
                 true
             }
             Msg::SetFilter(filter) => {
-                self.filter = filter;
+                if let Some(navigator) = ctx.link().navigator() {
+                    navigator.push(&Route::from(filter));
+                }
+                self.dispatch(Action::SetFilter(filter))
+            }
+            // Filter links and undo/redo set the filter before they navigate,
+            // so only a route the state does not show yet is dispatched
+            Msg::RouteChanged(route) => {
+                let filter = route_filter(route);
+                filter != self.state.filter && self.dispatch(Action::SetFilter(filter))
+            }
+            Msg::ToggleAll => self.dispatch(Action::ToggleAll),
+            Msg::ClearCompleted => self.dispatch(Action::ClearCompleted),
+            Msg::Undo => self.travel(ctx, "undo", History::undo),
+            Msg::Redo => self.travel(ctx, "redo", History::redo),
+            Msg::Bench(mut request) => {
+                let changed = match request.take_action() {
+                    Some(action) => self.dispatch(action),
+                    None => false,
+                };
+                request.resolve(&self.state);
+                changed
+            }
+            Msg::Scrolled { scroll_top, height } => match &mut self.viewport {
+                Some(viewport) => {
+                    let total = self.state.filtered().count();
+                    let before = viewport.visible(total);
+                    viewport.scroll_top = scroll_top;
+                    viewport.height = height;
+                    let after = viewport.visible(total);
+                    (before.start, before.end) != (after.start, after.end)
+                }
+                None => false,
+            },
+            Msg::StartEdit(id) => match self.state.todos.iter().find(|t| t.id == id) {
+                Some(todo) => {
+                    self.edit_value = todo.text.clone();
+                    self.editing = Some(id);
+                    true
+                }
+                None => false,
+            },
+            Msg::UpdateEdit(value) => {
+                self.edit_value = value;
                 true
             }
+            // Also sent by the blur after Enter/Escape, when `editing` is
+            // already taken, see `Action::Edit`
+            Msg::CommitEdit => match self.editing.take() {
+                Some(id) => {
+                    let text = std::mem::take(&mut self.edit_value);
+                    self.dispatch(Action::Edit(id, text));
+                    true
+                }
+                None => false,
+            },
+            Msg::CancelEdit => {
+                self.edit_value.clear();
+                self.editing.take().is_some()
+            }
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        // The hydrate build applies the hash filter once the page is hydrated
+        if first_render && cfg!(feature = "hydrate") {
+            ctx.link().send_message(Msg::RouteChanged(ctx.link().route()));
+        }
+        if let Some(input) = self.edit_input.cast::<HtmlInputElement>() {
+            let _ = input.focus();
+        }
+        // `list_mounted` is reset when the empty state removed the list, so
+        // a new element is scrolled once, see `VisibleRange::scroll_top`
+        let list = self.list_ref.cast::<Element>();
+        if let (Some(viewport), Some(list), false) = (self.viewport, &list, self.list_mounted) {
+            list.set_scroll_top(viewport.visible(self.state.filtered().count()).scroll_top);
         }
+        self.list_mounted = list.is_some();
+        self.marks.rendered();
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
 
-        let filtered_todos: Vec<&Todo> = self
-            .todos
-            .iter()
-            .filter(|todo| match self.filter {
-                Filter::All => true,
-                Filter::Active => !todo.completed,
-                Filter::Completed => todo.completed,
-            })
-            .collect();
-
-        let remaining_count = self.todos.iter().filter(|t| !t.completed).count();
+        let filtered_todos: Vec<&Todo> = self.state.filtered().collect();
+        let range = match self.viewport {
+            Some(viewport) => viewport.visible(filtered_todos.len()),
+            None => VisibleRange::all(filtered_todos.len()),
+        };
+        let remaining_count = self.state.remaining_count();
+        let completed_count = self.state.completed_count();
 
         let on_input = link.callback(|e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
@@ -151,21 +308,21 @@ impl Component for App {
 
                 <div class="todo-filters">
                     <button
-                        class={classes!("btn", "filter-btn", if matches!(self.filter, Filter::All) { "active" } else { "" })}
+                        class={classes!("btn", "filter-btn", if matches!(self.state.filter, Filter::All) { "active" } else { "" })}
                         onclick={link.callback(|_| Msg::SetFilter(Filter::All))}
                         aria-label="Show all todos"
                     >
                         {"All"}
                     </button>
                     <button
-                        class={classes!("btn", "filter-btn", if matches!(self.filter, Filter::Active) { "active" } else { "" })}
+                        class={classes!("btn", "filter-btn", if matches!(self.state.filter, Filter::Active) { "active" } else { "" })}
                         onclick={link.callback(|_| Msg::SetFilter(Filter::Active))}
                         aria-label="Show active todos"
                     >
                         {"Active"}
                     </button>
                     <button
-                        class={classes!("btn", "filter-btn", if matches!(self.filter, Filter::Completed) { "active" } else { "" })}
+                        class={classes!("btn", "filter-btn", if matches!(self.state.filter, Filter::Completed) { "active" } else { "" })}
                         onclick={link.callback(|_| Msg::SetFilter(Filter::Completed))}
                         aria-label="Show completed todos"
                     >
@@ -174,7 +331,28 @@ impl Component for App {
                 </div>
 
                 <div class="todo-stats">
-                    {remaining_count}{" "}{if remaining_count == 1 { "item" } else { "items" }}{" remaining"}
+                    {remaining_label(remaining_count)}
+                </div>
+
+                <div class="todo-bulk-actions">
+                    <label class="toggle-all-label">
+                        <input
+                            type="checkbox"
+                            class="toggle-all"
+                            checked={self.state.all_completed()}
+                            onchange={link.callback(|_| Msg::ToggleAll)}
+                            aria-label="Toggle all todos"
+                        />
+                        {"Mark all as complete"}
+                    </label>
+                    <button
+                        class="btn btn-clear-completed"
+                        disabled={completed_count == 0}
+                        onclick={link.callback(|_| Msg::ClearCompleted)}
+                        aria-label="Clear completed todos"
+                    >
+                        {"Clear completed"}
+                    </button>
                 </div>
 
                 {if filtered_todos.is_empty() {
@@ -186,12 +364,55 @@ impl Component for App {
                     }
                 } else {
                     html! {
-                        <ul class="todo-list">
-                            {for filtered_todos.iter().map(|todo| {
+                        <ul
+                            class={classes!("todo-list", if self.viewport.is_some() { "virtual" } else { "" })}
+                            ref={self.list_ref.clone()}
+                            onscroll={self.viewport.is_some().then(|| link.callback(|e: Event| {
+                                let list: Element = e.target_unchecked_into();
+                                Msg::Scrolled { scroll_top: list.scroll_top(), height: list.client_height() }
+                            }))}
+                        >
+                            {spacer(range.padding_top)}
+                            {for filtered_todos[range.start..range.end].iter().enumerate().map(|(offset, todo)| {
+                                let index = range.start + offset;
                                 let id = todo.id;
                                 let completed = todo.completed;
+                                let selected = self.state.selected == Some(id);
+                                let text = if self.editing == Some(id) {
+                                    html! {
+                                        <input
+                                            type="text"
+                                            class="todo-edit"
+                                            ref={self.edit_input.clone()}
+                                            value={self.edit_value.clone()}
+                                            oninput={link.callback(|e: InputEvent| {
+                                                let input: HtmlInputElement = e.target_unchecked_into();
+                                                Msg::UpdateEdit(input.value())
+                                            })}
+                                            onkeydown={link.batch_callback(|e: KeyboardEvent| match e.key().as_str() {
+                                                "Enter" => Some(Msg::CommitEdit),
+                                                "Escape" => Some(Msg::CancelEdit),
+                                                _ => None,
+                                            })}
+                                            onblur={link.callback(|_| Msg::CommitEdit)}
+                                            aria-label={format!("Edit {}", todo.text)}
+                                        />
+                                    }
+                                } else {
+                                    html! {
+                                        <span
+                                            class="todo-text"
+                                            ondblclick={link.callback(move |_| Msg::StartEdit(id))}
+                                        >
+                                            {&todo.text}
+                                        </span>
+                                    }
+                                };
                                 html! {
-                                    <li class={classes!("todo-item", if completed { "completed" } else { "" })}>
+                                    <li
+                                        key={self.list_mode.key(index, id)}
+                                        class={classes!("todo-item", if completed { "completed" } else { "" }, if selected { "selected" } else { "" })}
+                                    >
                                         <input
                                             type="checkbox"
                                             class="todo-checkbox"
@@ -199,7 +420,7 @@ impl Component for App {
                                             onchange={link.callback(move |_| Msg::ToggleTodo(id))}
                                             aria-label={format!("Toggle {}", todo.text)}
                                         />
-                                        <span class="todo-text">{&todo.text}</span>
+                                        {text}
                                         <button
                                             class="btn btn-delete"
                                             onclick={link.callback(move |_| Msg::DeleteTodo(id))}
@@ -210,6 +431,7 @@ impl Component for App {
                                     </li>
                                 }
                             })}
+                            {spacer(range.padding_bottom)}
                         </ul>
                     }
                 }}
@@ -222,6 +444,51 @@ impl Component for App {
     }
 }
 
+/// Stands in for the rows a virtualized list does not render.
+fn spacer(height: usize) -> Html {
+    if height == 0 {
+        return html! {};
+    }
+    html! {
+        <li class="todo-spacer" style={format!("height: {}px", height)} aria-hidden="true"></li>
+    }
+}
+
+/// Unknown hashes fall back to showing all todos.
+fn route_filter(route: Option<Route>) -> Filter {
+    route.map(Filter::from).unwrap_or_default()
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "hydrate")))]
 fn main() {
-    yew::Renderer::<App>::new().render();
+    yew::Renderer::<Root>::new().render();
+}
+
+/// The hydrate build takes over the markup streamed by `server.rs`.
+#[cfg(all(target_arch = "wasm32", feature = "hydrate"))]
+fn main() {
+    use yew_router::history::{AnyHistory, HashHistory};
+
+    let props = route::ServerRootProps {
+        seed: SeedConfig::from_url(),
+        history: AnyHistory::from(HashHistory::new()),
+    };
+    yew::Renderer::<route::ServerRoot>::with_props(props).hydrate();
+}
+
+/// With `--features ssr`, serves the SSR + hydration build.
+#[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    server::serve().await
+}
+
+/// Natively, prints the markup of `ssr.rs` for the arguments, see
+/// `todo_core::preview`.
+#[cfg(all(not(feature = "ssr"), not(target_arch = "wasm32")))]
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let seed = SeedConfig::from_args(std::env::args().skip(1));
+    let preview = Preview::from_args(std::env::args().skip(1));
+    println!("{}", ssr::render_to_string(seed, preview).await);
 }
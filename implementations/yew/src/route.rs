@@ -0,0 +1,70 @@
+use todo_core::seed::SeedConfig;
+use todo_core::Filter;
+use yew::prelude::*;
+use yew_router::history::{AnyHistory, MemoryHistory};
+use yew_router::prelude::*;
+
+use crate::App;
+
+/// The active filter, mirrored in the URL hash (`#/`, `#/active`, `#/completed`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Routable)]
+pub enum Route {
+    #[at("/")]
+    All,
+    #[at("/active")]
+    Active,
+    #[at("/completed")]
+    Completed,
+}
+
+impl From<Route> for Filter {
+    fn from(route: Route) -> Self {
+        match route {
+            Route::All => Filter::All,
+            Route::Active => Filter::Active,
+            Route::Completed => Filter::Completed,
+        }
+    }
+}
+
+impl From<Filter> for Route {
+    fn from(filter: Filter) -> Self {
+        match filter {
+            Filter::All => Route::All,
+            Filter::Active => Route::Active,
+            Filter::Completed => Route::Completed,
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "hydrate")))]
+#[function_component(Root)]
+pub fn root() -> Html {
+    html! {
+        <HashRouter>
+            <App />
+        </HashRouter>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ServerRootProps {
+    pub seed: SeedConfig,
+    /// An in-memory history at `/` unless given.
+    #[prop_or_else(|| AnyHistory::from(MemoryHistory::new()))]
+    pub history: AnyHistory,
+}
+
+/// The app with a fixed seed, for rendering outside the browser. The
+/// hydrating client renders it too, with the hash history, because
+/// hydration needs the same component tree as the server.
+#[function_component(ServerRoot)]
+pub fn server_root(props: &ServerRootProps) -> Html {
+    html! {
+        <Router history={props.history.clone()}>
+            <ContextProvider<SeedConfig> context={props.seed}>
+                <App />
+            </ContextProvider<SeedConfig>>
+        </Router>
+    }
+}
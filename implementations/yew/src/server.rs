@@ -0,0 +1,16 @@
+//! The axum server of the SSR + hydration build (`--features ssr`), see
+//! `todo_core::ssr`; each page is rendered with `yew::ServerRenderer`.
+
+use todo_core::ssr;
+use yew::{props, ServerRenderer};
+
+use crate::route::{ServerRoot, ServerRootProps};
+
+pub async fn serve() -> std::io::Result<()> {
+    ssr::serve("Yew", |seed, _url| {
+        ServerRenderer::<ServerRoot>::with_props(move || props!(ServerRootProps { seed }))
+            .hydratable(true)
+            .render_stream()
+    })
+    .await
+}
@@ -0,0 +1,47 @@
+//! Native rendering of the app to an HTML string with `yew::ServerRenderer`.
+//!
+//! Used by `cargo run` to print the markup and by `cargo test` to check it
+//! without a browser.
+
+use todo_core::preview::Preview;
+use todo_core::seed::SeedConfig;
+use yew::{function_component, html, props, ContextProvider, Html, Properties, ServerRenderer};
+
+use crate::route::ServerRoot;
+
+#[derive(Properties, PartialEq)]
+struct PreviewRootProps {
+    seed: SeedConfig,
+    preview: Preview,
+}
+
+/// [`ServerRoot`] in a preview state, which only this renderer provides.
+#[function_component(PreviewRoot)]
+fn preview_root(props: &PreviewRootProps) -> Html {
+    html! {
+        <ContextProvider<Preview> context={props.preview}>
+            <ServerRoot seed={props.seed} />
+        </ContextProvider<Preview>>
+    }
+}
+
+// The `ssr` server streams instead, see `server.rs`
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+pub async fn render_to_string(seed: SeedConfig, preview: Preview) -> String {
+    ServerRenderer::<PreviewRoot>::with_props(move || props!(PreviewRootProps { seed, preview }))
+        .hydratable(false)
+        .render()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn renders_the_fixtures() {
+        for fixture in conformance::fixtures::all() {
+            fixture.check("Yew", &render_to_string(fixture.seed, Preview::default()).await);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Just enough CSS handling for the shared stylesheet: minifying it and
+//! listing the classes its selectors use.
+
+use std::collections::BTreeSet;
+
+/// Strips comments and whitespace that does not change the meaning:
+/// around `{`, `}`, `;`, `,` and `>`, inside parentheses, around `:`
+/// except before a pseudo-class in a selector (`a :hover`), and the last
+/// `;` of a block.
+/// Strings are copied as they are.
+pub fn minify(css: &str) -> String {
+    // No space is needed after these, nor before them but `(`
+    const TIGHT: [char; 7] = ['{', '}', ';', ',', '>', ':', '('];
+
+    let css = without_comments(css);
+    let mut out = String::with_capacity(css.len());
+    // Whether each open block holds declarations rather than rules
+    let mut blocks: Vec<bool> = Vec::new();
+    let mut prelude_start = 0;
+    let mut space = false;
+    let mut chars = css.chars();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            space = true;
+            continue;
+        }
+        let space_before = std::mem::take(&mut space) && !out.is_empty();
+        let in_declarations = blocks.last().copied().unwrap_or(false);
+
+        match c {
+            '{' => {
+                let at_rule = out[prelude_start..].starts_with('@');
+                blocks.push(in_declarations || !at_rule);
+            }
+            '}' => {
+                blocks.pop();
+                if out.ends_with(';') {
+                    out.pop();
+                }
+            }
+            ':' if space_before && !in_declarations && !out.ends_with(TIGHT) => out.push(' '),
+            ':' | ')' => {}
+            c if space_before && (c == '(' || !TIGHT.contains(&c)) && !out.ends_with(TIGHT) => {
+                out.push(' ')
+            }
+            _ => {}
+        }
+        out.push(c);
+        if matches!(c, '{' | '}' | ';') {
+            prelude_start = out.len();
+        }
+
+        if c == '"' || c == '\'' {
+            for inner in chars.by_ref() {
+                out.push(inner);
+                if inner == c {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Every class used in a selector of the stylesheet.
+pub fn class_selectors(css: &str) -> BTreeSet<String> {
+    let css = without_comments(css);
+    let mut classes = BTreeSet::new();
+    // Depth of `{` and whether each open block holds declarations, whose
+    // values (`0.5s`, `url(a.png)`) must not be read as selectors
+    let mut blocks: Vec<bool> = Vec::new();
+    let mut prelude = String::new();
+
+    for c in css.chars() {
+        let in_declarations = blocks.last().copied().unwrap_or(false);
+        match c {
+            '{' => {
+                let at_rule = prelude.trim_start().starts_with('@');
+                if !in_declarations && !at_rule {
+                    collect_classes(&prelude, &mut classes);
+                }
+                // `@media` and `@supports` hold rules; other at-rules such
+                // as `@keyframes` are skipped whole
+                let holds_rules = at_rule
+                    && ["@media", "@supports", "@container", "@layer"]
+                        .iter()
+                        .any(|rule| prelude.trim_start().starts_with(rule));
+                blocks.push(in_declarations || !holds_rules);
+                prelude.clear();
+            }
+            '}' => {
+                blocks.pop();
+                prelude.clear();
+            }
+            ';' if !in_declarations => prelude.clear(),
+            c if !in_declarations => prelude.push(c),
+            _ => {}
+        }
+    }
+    classes
+}
+
+fn collect_classes(selector: &str, classes: &mut BTreeSet<String>) {
+    let mut rest = selector;
+    while let Some(dot) = rest.find('.') {
+        rest = &rest[dot + 1..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        let class = &rest[..len];
+        if class.starts_with(|c: char| c.is_ascii_alphabetic() || c == '-' || c == '_') {
+            classes.insert(class.to_string());
+        }
+        rest = &rest[len..];
+    }
+}
+
+fn without_comments(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = rest[start + 2..]
+            .find("*/")
+            .map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSS: &str = "/* Shared */
+.todo-item.completed .todo-text,
+.todo-list > li:hover {
+  font-family: 'Segoe UI',  sans-serif;
+  transition: opacity 0.3s ease;
+}
+
+@keyframes fadeIn {
+  from { opacity: 0; }
+}
+
+@media screen and (max-width: 600.5px) {
+  .btn :focus { padding: 0 calc(1rem + 2px); }
+}
+";
+
+    #[test]
+    fn minifies_without_changing_meaning() {
+        assert_eq!(
+            minify(CSS),
+            ".todo-item.completed .todo-text,.todo-list>li:hover{font-family:'Segoe UI',sans-serif;transition:opacity 0.3s ease}\
+             @keyframes fadeIn{from{opacity:0}}\
+             @media screen and (max-width:600.5px){.btn :focus{padding:0 calc(1rem + 2px)}}"
+        );
+    }
+
+    #[test]
+    fn lists_classes_of_selectors_only() {
+        let classes: Vec<String> = class_selectors(CSS).into_iter().collect();
+        assert_eq!(
+            classes,
+            ["btn", "completed", "todo-item", "todo-list", "todo-text"]
+        );
+    }
+}
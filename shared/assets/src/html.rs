@@ -0,0 +1,83 @@
+//! The two things the pipeline needs from HTML: the classes a page renders
+//! and the `<link>` to the shared stylesheet in `index.html`.
+
+use std::collections::BTreeSet;
+
+/// The source path every app's `index.html` links, relative to the repo.
+pub const SHARED_STYLESHEET: &str = "shared/styles/todo.css";
+
+/// Every class in the `class` attributes of `html`.
+pub fn rendered_classes(html: &str) -> BTreeSet<String> {
+    let mut classes = BTreeSet::new();
+    let mut rest = html;
+    while let Some(at) = rest.find("class=") {
+        rest = &rest[at + "class=".len()..];
+        // Only whole attribute names, not `data-class=` or `classes=`
+        let before = html.len() - rest.len() - "class=".len();
+        if before > 0 && !html[..before].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let value = &rest[1..];
+        let end = value.find(quote).unwrap_or(value.len());
+        classes.extend(value[..end].split_whitespace().map(str::to_string));
+        rest = &value[end..];
+    }
+    classes
+}
+
+/// Replaces the `<link>` to the shared stylesheet with one to `href`, or
+/// adds it at the end of `<head>` when there is none.
+pub fn link_stylesheet(index_html: &str, href: &str) -> String {
+    let link = format!("<link rel=\"stylesheet\" href=\"{}\">", href);
+
+    let mut search = 0;
+    while let Some(at) = index_html[search..].find("<link") {
+        let start = search + at;
+        let end = index_html[start..]
+            .find('>')
+            .map_or(index_html.len(), |end| start + end + 1);
+        let tag = &index_html[start..end];
+        if tag.contains(&format!("{}\"", SHARED_STYLESHEET)) && !tag.contains("data-trunk") {
+            return format!("{}{}{}", &index_html[..start], link, &index_html[end..]);
+        }
+        search = end;
+    }
+
+    match index_html.find("</head>") {
+        Some(head) => format!(
+            "{}    {}\n{}",
+            &index_html[..head],
+            link,
+            &index_html[head..]
+        ),
+        None => format!("{}{}", link, index_html),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_class_attributes_only() {
+        let html = r#"<li class="todo-item  completed" data-class="x"><span class='todo-text'>class="no"</span></li>"#;
+        let classes: Vec<String> = rendered_classes(html).into_iter().collect();
+        assert_eq!(classes, ["completed", "todo-item", "todo-text"]);
+    }
+
+    #[test]
+    fn replaces_the_shared_stylesheet_link() {
+        let index = "<head>\n    <link rel=\"stylesheet\" href=\"../../shared/styles/todo.css\">\n    <link data-trunk rel=\"rust\" />\n</head>";
+        assert_eq!(
+            link_stylesheet(index, "/todo-0123456789abcdef.css"),
+            "<head>\n    <link rel=\"stylesheet\" href=\"/todo-0123456789abcdef.css\">\n    <link data-trunk rel=\"rust\" />\n</head>"
+        );
+        assert_eq!(
+            link_stylesheet("<head></head>", "/todo.css"),
+            "<head>    <link rel=\"stylesheet\" href=\"/todo.css\">\n</head>"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! What the build steps outside `todo-assets` share with it: `cargo xtask
+//! build` names the app bundle the way the stylesheet is named here.
+
+/// 64-bit FNV-1a, enough to tell versions of a build output apart.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,174 @@
+//! Bundles `shared/styles/todo.css` into a Rust app's build output.
+//!
+//! The stylesheet is minified and written as `todo-<hash>.css` next to the
+//! app's `index.html`, whose link to the source file is pointed at it, so
+//! every framework ships the same bytes under a cacheable name. Before
+//! that, the app is run natively for a few states and every class
+//! its markup renders must have a selector in the stylesheet; a missing
+//! one fails the build.
+//!
+//! Trunk runs it as a `post_build` hook, which provides the directories
+//! through `TRUNK_*` variables; the Dioxus Dockerfile passes them as
+//! options.
+
+mod css;
+mod html;
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use todo_assets::fnv1a;
+
+const USAGE: &str = "\
+Usage: todo-assets [OPTIONS]
+
+Options:
+  --app <DIR>         App whose markup is checked [env: TRUNK_SOURCE_DIR]
+  --dist <DIR>        Build output holding index.html [env: TRUNK_STAGING_DIR]
+  --public-url <URL>  Prefix of the stylesheet's URL [env: TRUNK_PUBLIC_URL, default: /]
+  --no-check          Skip checking the rendered classes
+  -h, --help          Print this help";
+
+/// States the app renders for the check: the empty list, one with both
+/// active and completed todos plus a selected row and an open edit field,
+/// and the virtualized default list, whose spacers only appear once it is
+/// taller than the viewport.
+const STATES: &[&[&str]] = &[
+    &["count=0"],
+    &[
+        "count=12",
+        "seed=7",
+        "completedRatio=0.5",
+        "selected=2",
+        "editing=3",
+    ],
+    &["virtual=1"],
+];
+
+fn stylesheet_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../styles/todo.css")
+}
+
+fn main() {
+    let mut app = std::env::var_os("TRUNK_SOURCE_DIR").map(PathBuf::from);
+    let mut dist = std::env::var_os("TRUNK_STAGING_DIR").map(PathBuf::from);
+    let mut public_url = std::env::var("TRUNK_PUBLIC_URL").unwrap_or_else(|_| "/".to_string());
+    let mut check = true;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--app" => match args.next() {
+                Some(dir) => app = Some(PathBuf::from(dir)),
+                None => usage_error("--app needs a directory"),
+            },
+            "--dist" => match args.next() {
+                Some(dir) => dist = Some(PathBuf::from(dir)),
+                None => usage_error("--dist needs a directory"),
+            },
+            "--public-url" => match args.next() {
+                Some(url) => public_url = url,
+                None => usage_error("--public-url needs a URL"),
+            },
+            "--no-check" => check = false,
+            other => usage_error(&format!("unknown argument {}", other)),
+        }
+    }
+    let Some(dist) = dist else {
+        usage_error("no --dist given and TRUNK_STAGING_DIR is not set");
+    };
+
+    let stylesheet = stylesheet_path();
+    let source = match std::fs::read_to_string(&stylesheet) {
+        Ok(source) => source,
+        Err(err) => fail(&format!("failed to read {}: {}", stylesheet.display(), err)),
+    };
+
+    if check {
+        let Some(app) = app else {
+            usage_error("no --app given and TRUNK_SOURCE_DIR is not set");
+        };
+        let missing = missing_selectors(&app, &css::class_selectors(&source));
+        if !missing.is_empty() {
+            eprintln!(
+                "error: {} renders classes that {} has no selector for:",
+                app.display(),
+                html::SHARED_STYLESHEET
+            );
+            for class in &missing {
+                eprintln!("  .{}", class);
+            }
+            std::process::exit(1);
+        }
+    }
+
+    let minified = css::minify(&source);
+    let file_name = format!("todo-{:016x}.css", fnv1a(minified.as_bytes()));
+    if let Err(err) = std::fs::write(dist.join(&file_name), &minified) {
+        fail(&format!("failed to write {}: {}", file_name, err));
+    }
+
+    let index = dist.join("index.html");
+    let page = match std::fs::read_to_string(&index) {
+        Ok(page) => page,
+        Err(err) => fail(&format!("failed to read {}: {}", index.display(), err)),
+    };
+    let href = format!("{}/{}", public_url.trim_end_matches('/'), file_name);
+    if let Err(err) = std::fs::write(&index, html::link_stylesheet(&page, &href)) {
+        fail(&format!("failed to write {}: {}", index.display(), err));
+    }
+
+    println!(
+        "Bundled {} ({} -> {} bytes) as {}",
+        html::SHARED_STYLESHEET,
+        source.len(),
+        minified.len(),
+        href
+    );
+}
+
+/// Classes the app renders in any of [`STATES`] that no selector uses.
+fn missing_selectors(app: &Path, selectors: &BTreeSet<String>) -> BTreeSet<String> {
+    let manifest = app.join("Cargo.toml");
+    let mut rendered = BTreeSet::new();
+    for state in STATES {
+        // The app's own target directory, as its native build is not the
+        // one Trunk just made
+        let output = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+            .args(["run", "--quiet", "--manifest-path"])
+            .arg(&manifest)
+            .env("CARGO_TARGET_DIR", app.join("target"))
+            .arg("--")
+            .args(*state)
+            .output();
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => fail(&format!(
+                "{} exited with {}:\n{}",
+                manifest.display(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )),
+            Err(err) => fail(&format!("failed to run {}: {}", manifest.display(), err)),
+        };
+        rendered.extend(html::rendered_classes(&String::from_utf8_lossy(
+            &output.stdout,
+        )));
+    }
+    rendered.difference(selectors).cloned().collect()
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,90 @@
+//! The `window.__bench` API used by the runtime benchmarks.
+//!
+//! It mirrors the js-framework-benchmark operations. Every method returns a
+//! promise that resolves once the app has applied the request through its
+//! normal update path; `state()` resolves with a JSON dump of the state.
+
+use std::rc::Rc;
+
+use js_sys::{Function, Object, Promise, Reflect, JSON};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsValue;
+
+use crate::{Action, TodoState};
+
+/// Name of the global the API is installed as.
+pub const BENCH_GLOBAL: &str = "__bench";
+
+/// Maps a method's optional todo id argument to the action it dispatches.
+type ToAction = fn(Option<usize>) -> Option<Action>;
+
+/// A call from JavaScript, answered with [`BenchRequest::resolve`].
+pub struct BenchRequest {
+    action: Option<Action>,
+    dump_state: bool,
+    resolve: Function,
+}
+
+impl BenchRequest {
+    /// The action to dispatch, or `None` for a `state()` dump.
+    pub fn take_action(&mut self) -> Option<Action> {
+        self.action.take()
+    }
+
+    /// Resolves the caller's promise, with the state for `state()` calls.
+    pub fn resolve(self, state: &TodoState) {
+        let value = if self.dump_state {
+            serde_json::to_string(state)
+                .ok()
+                .and_then(|json| JSON::parse(&json).ok())
+                .unwrap_or(JsValue::NULL)
+        } else {
+            JsValue::UNDEFINED
+        };
+        let _ = self.resolve.call1(&JsValue::NULL, &value);
+    }
+}
+
+/// Installs `window.__bench`, handing every call to `handler`.
+pub fn install_bench_api(handler: impl Fn(BenchRequest) + 'static) {
+    let Some(window) = crate::web::window() else {
+        return;
+    };
+    let handler: Rc<dyn Fn(BenchRequest)> = Rc::new(handler);
+    let api = Object::new();
+
+    let methods: [(&str, ToAction); 9] = [
+        ("run", |_| Some(Action::Create(1_000))),
+        ("runLots", |_| Some(Action::Create(10_000))),
+        ("add", |_| Some(Action::Append(1_000))),
+        ("update", |_| Some(Action::UpdateEvery(10))),
+        ("swapRows", |_| Some(Action::Swap(1, 998))),
+        ("select", |id| id.map(Action::Select)),
+        ("remove", |id| id.map(Action::Delete)),
+        ("clear", |_| Some(Action::Clear)),
+        ("state", |_| None),
+    ];
+
+    for (name, to_action) in methods {
+        let handler = handler.clone();
+        let takes_id = matches!(name, "select" | "remove");
+        let method = Closure::<dyn Fn(JsValue) -> Promise>::new(move |arg: JsValue| {
+            let id = arg.as_f64().map(|id| id as usize);
+            if takes_id && id.is_none() {
+                return Promise::reject(&format!("__bench.{}() needs a todo id", name).into());
+            }
+            let action = to_action(id);
+            let handler = handler.clone();
+            Promise::new(&mut |resolve, _reject| {
+                handler(BenchRequest {
+                    action: action.clone(),
+                    dump_state: action.is_none(),
+                    resolve,
+                })
+            })
+        });
+        let _ = Reflect::set(&api, &name.into(), &method.into_js_value());
+    }
+
+    let _ = Reflect::set(&window, &BENCH_GLOBAL.into(), &api);
+}
@@ -0,0 +1,365 @@
+//! Bounded undo/redo history for [`TodoState`].
+//!
+//! Each entry records what an [`Action`] changed rather than a snapshot of
+//! the whole list, so recording a toggle stays cheap at 100k todos.
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::{Action, Filter, Todo, TodoState};
+
+/// Number of undo steps kept before the oldest one is dropped.
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Change {
+    Insert {
+        index: usize,
+        todo: Todo,
+    },
+    /// Removed todos with their original indices, in ascending order, and
+    /// the selected id if it was one of them.
+    Remove {
+        removed: Vec<(usize, Todo)>,
+        selected: Option<usize>,
+    },
+    Toggle(HashSet<usize>),
+    Edit {
+        id: usize,
+        before: String,
+        after: String,
+    },
+    Filter {
+        before: Filter,
+        after: Filter,
+    },
+}
+
+impl Change {
+    fn undo(&self, state: &mut TodoState) {
+        match self {
+            Change::Insert { index, .. } => {
+                let todo = state.todos.remove(*index);
+                if state.selected == Some(todo.id) {
+                    state.selected = None;
+                }
+            }
+            Change::Remove { removed, selected } => {
+                restore(&mut state.todos, removed);
+                if selected.is_some() {
+                    state.selected = *selected;
+                }
+            }
+            Change::Toggle(ids) => toggle(&mut state.todos, ids),
+            Change::Edit { id, before, .. } => set_text(&mut state.todos, *id, before),
+            Change::Filter { before, .. } => state.filter = *before,
+        }
+    }
+
+    fn redo(&self, state: &mut TodoState) {
+        match self {
+            Change::Insert { index, todo } => state.todos.insert(*index, todo.clone()),
+            Change::Remove { removed, selected } => {
+                let ids: HashSet<usize> = removed.iter().map(|(_, todo)| todo.id).collect();
+                state.todos.retain(|t| !ids.contains(&t.id));
+                if selected.is_some() {
+                    state.selected = None;
+                }
+            }
+            Change::Toggle(ids) => toggle(&mut state.todos, ids),
+            Change::Edit { id, after, .. } => set_text(&mut state.todos, *id, after),
+            Change::Filter { after, .. } => state.filter = *after,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct History {
+    undo: VecDeque<Change>,
+    redo: Vec<Change>,
+    limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl History {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Applies `action` like [`TodoState::apply`] and records it for undo.
+    ///
+    /// Recording a change discards everything that could be redone.
+    /// Benchmark operations other than `Select` replace or reorder rows
+    /// wholesale, so they clear the history instead of being recorded.
+    pub fn apply(&mut self, state: &mut TodoState, action: Action) -> bool {
+        if action.is_benchmark() {
+            let resets = !matches!(action, Action::Select(_));
+            let changed = state.apply(action);
+            if changed && resets {
+                self.undo.clear();
+                self.redo.clear();
+            }
+            return changed;
+        }
+
+        let change = match &action {
+            Action::Add(_) => None,
+            Action::Toggle(id) => Some(Change::Toggle(HashSet::from([*id]))),
+            Action::Delete(id) => Some(remove(state, |t| t.id == *id)),
+            Action::Edit(id, text) if text.trim().is_empty() => {
+                Some(remove(state, |t| t.id == *id))
+            }
+            Action::Edit(id, text) => {
+                state
+                    .todos
+                    .iter()
+                    .find(|t| t.id == *id)
+                    .map(|todo| Change::Edit {
+                        id: *id,
+                        before: todo.text.clone(),
+                        after: text.trim().to_string(),
+                    })
+            }
+            Action::ToggleAll => {
+                let completed = !state.all_completed();
+                Some(Change::Toggle(
+                    state
+                        .todos
+                        .iter()
+                        .filter(|t| t.completed != completed)
+                        .map(|t| t.id)
+                        .collect(),
+                ))
+            }
+            Action::ClearCompleted => Some(remove(state, |t| t.completed)),
+            Action::SetFilter(filter) => Some(Change::Filter {
+                before: state.filter,
+                after: *filter,
+            }),
+            Action::Create(_)
+            | Action::Append(_)
+            | Action::UpdateEvery(_)
+            | Action::Swap(..)
+            | Action::Select(_)
+            | Action::Clear => unreachable!("benchmark actions are handled above"),
+        };
+
+        if !state.apply(action) {
+            return false;
+        }
+        // Add is the only action whose change is known only afterwards.
+        let change = change.unwrap_or_else(|| Change::Insert {
+            index: 0,
+            todo: state.todos[0].clone(),
+        });
+
+        if self.undo.len() == self.limit {
+            self.undo.pop_front();
+        }
+        if self.limit > 0 {
+            self.undo.push_back(change);
+        }
+        self.redo.clear();
+        true
+    }
+
+    /// Reverts the most recent change; returns false if there is none.
+    pub fn undo(&mut self, state: &mut TodoState) -> bool {
+        match self.undo.pop_back() {
+            Some(change) => {
+                change.undo(state);
+                self.redo.push(change);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-applies the most recently undone change; returns false if there is none.
+    pub fn redo(&mut self, state: &mut TodoState) -> bool {
+        match self.redo.pop() {
+            Some(change) => {
+                change.redo(state);
+                self.undo.push_back(change);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The change that removing the todos matching `predicate` will make.
+fn remove(state: &TodoState, predicate: impl Fn(&Todo) -> bool) -> Change {
+    let removed: Vec<(usize, Todo)> = state
+        .todos
+        .iter()
+        .enumerate()
+        .filter(|(_, todo)| predicate(todo))
+        .map(|(index, todo)| (index, todo.clone()))
+        .collect();
+    let selected = state
+        .selected
+        .filter(|id| removed.iter().any(|(_, todo)| todo.id == *id));
+    Change::Remove { removed, selected }
+}
+
+/// Puts removed todos back at their original indices in one pass.
+fn restore(todos: &mut Vec<Todo>, removed: &[(usize, Todo)]) {
+    let mut remaining = std::mem::take(todos).into_iter();
+    let mut removed = removed.iter().peekable();
+    let len = remaining.len() + removed.len();
+    let mut restored = Vec::with_capacity(len);
+    while restored.len() < len {
+        match removed.next_if(|(index, _)| *index == restored.len()) {
+            Some((_, todo)) => restored.push(todo.clone()),
+            None => match remaining.next() {
+                Some(todo) => restored.push(todo),
+                None => break,
+            },
+        }
+    }
+    *todos = restored;
+}
+
+fn toggle(todos: &mut [Todo], ids: &HashSet<usize>) {
+    for todo in todos.iter_mut().filter(|t| ids.contains(&t.id)) {
+        todo.completed = !todo.completed;
+    }
+}
+
+fn set_text(todos: &mut [Todo], id: usize, text: &str) {
+    if let Some(todo) = todos.iter_mut().find(|t| t.id == id) {
+        todo.text = text.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(state: &TodoState) -> Vec<usize> {
+        state.todos.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn undo_restores_a_deleted_todo_and_its_selection() {
+        let mut state = TodoState::seeded(3);
+        let mut history = History::default();
+        history.apply(&mut state, Action::Select(2));
+        assert!(history.apply(&mut state, Action::Delete(2)));
+        assert_eq!((ids(&state), state.selected), (vec![1, 3], None));
+
+        assert!(history.undo(&mut state));
+        assert_eq!((ids(&state), state.selected), (vec![1, 2, 3], Some(2)));
+        assert!(history.redo(&mut state));
+        assert_eq!((ids(&state), state.selected), (vec![1, 3], None));
+    }
+
+    #[test]
+    fn undo_and_redo_every_recorded_action() {
+        let initial = TodoState::seeded(4);
+        let mut state = initial.clone();
+        let mut history = History::default();
+        let actions = [
+            Action::Add("New".to_string()),
+            Action::Toggle(1),
+            Action::Edit(2, "Renamed".to_string()),
+            Action::Edit(4, " ".to_string()),
+            Action::ToggleAll,
+            Action::ClearCompleted,
+            Action::SetFilter(Filter::Active),
+        ];
+        let mut states = vec![state.clone()];
+        for action in actions {
+            assert!(history.apply(&mut state, action));
+            states.push(state.clone());
+        }
+
+        // Undoing an add keeps `next_id`, so its id is never handed out again
+        let shown = |state: &TodoState| (state.todos.clone(), state.filter);
+        for expected in states.iter().rev().skip(1) {
+            assert!(history.undo(&mut state));
+            assert_eq!(shown(&state), shown(expected));
+        }
+        assert!(!history.undo(&mut state));
+        assert_eq!(shown(&state), shown(&initial));
+
+        for expected in states.iter().skip(1) {
+            assert!(history.redo(&mut state));
+            assert_eq!(shown(&state), shown(expected));
+        }
+        assert!(!history.redo(&mut state));
+    }
+
+    #[test]
+    fn a_new_action_clears_redo() {
+        let mut state = TodoState::seeded(2);
+        let mut history = History::default();
+        history.apply(&mut state, Action::Toggle(1));
+        history.undo(&mut state);
+        assert!(history.can_redo());
+
+        history.apply(&mut state, Action::Toggle(2));
+        assert!(!history.can_redo());
+        assert!(!history.redo(&mut state));
+    }
+
+    #[test]
+    fn unchanged_actions_are_not_recorded() {
+        let mut state = TodoState::seeded(2);
+        let mut history = History::default();
+        assert!(!history.apply(&mut state, Action::Delete(42)));
+        assert!(!history.apply(&mut state, Action::SetFilter(Filter::All)));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn evicts_the_oldest_change_past_the_limit() {
+        let mut state = TodoState::seeded(3);
+        let mut history = History::new(2);
+        for id in 1..=3 {
+            history.apply(&mut state, Action::Toggle(id));
+        }
+        assert!(history.undo(&mut state));
+        assert!(history.undo(&mut state));
+        assert!(!history.undo(&mut state));
+        // The first toggle was dropped, so it stays applied
+        assert!(state.todos[0].completed);
+        assert!(!state.todos[1].completed);
+
+        let mut history = History::new(0);
+        history.apply(&mut state, Action::Toggle(1));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn benchmark_actions_clear_history_except_select() {
+        let mut state = TodoState::seeded(3);
+        let mut history = History::default();
+        history.apply(&mut state, Action::Toggle(1));
+        history.apply(&mut state, Action::Toggle(2));
+        history.undo(&mut state);
+
+        assert!(history.apply(&mut state, Action::Select(3)));
+        assert!(history.can_undo() && history.can_redo());
+
+        assert!(history.apply(&mut state, Action::Swap(0, 1)));
+        assert!(!history.can_undo() && !history.can_redo());
+    }
+}
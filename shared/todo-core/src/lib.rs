@@ -0,0 +1,521 @@
+//! Shared Todo model and state logic for the Rust implementations.
+//!
+//! Yew, Leptos and Dioxus all render the same [`TodoState`] and mutate it
+//! only through [`TodoState::apply`], so the benchmarks compare rendering
+//! cost rather than three copies of the business logic.
+
+#[cfg(feature = "web")]
+pub mod bench;
+pub mod history;
+#[cfg(any(test, feature = "model"))]
+pub mod model;
+#[cfg(feature = "web")]
+pub mod perf;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod preview;
+pub mod seed;
+#[cfg(feature = "ssr")]
+pub mod ssr;
+#[cfg(feature = "serde")]
+pub mod storage;
+pub mod viewport;
+#[cfg(feature = "web")]
+pub mod web;
+
+/// Number of todos every implementation starts with.
+pub const INITIAL_TODO_COUNT: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Todo {
+    pub id: usize,
+    pub text: String,
+    pub completed: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Filter {
+    #[default]
+    All,
+    Active,
+    Completed,
+}
+
+impl Filter {
+    /// Route path for the filter, used as the URL hash (`#/active`).
+    pub fn path(self) -> &'static str {
+        match self {
+            Filter::All => "/",
+            Filter::Active => "/active",
+            Filter::Completed => "/completed",
+        }
+    }
+
+    pub fn from_path(path: &str) -> Option<Filter> {
+        match path {
+            "" | "/" => Some(Filter::All),
+            "/active" => Some(Filter::Active),
+            "/completed" => Some(Filter::Completed),
+            _ => None,
+        }
+    }
+
+    pub fn matches(self, todo: &Todo) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Active => !todo.completed,
+            Filter::Completed => todo.completed,
+        }
+    }
+}
+
+/// Every state transition the Todo app supports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Prepend a new todo; text that is empty after trimming is ignored.
+    Add(String),
+    Toggle(usize),
+    Delete(usize),
+    /// Replace a todo's text (trimmed); empty text deletes the todo.
+    ///
+    /// Views dispatch it when Enter or a blur ends an edit, while Escape
+    /// drops it. Removing the edit field after Enter or Escape fires a blur
+    /// too, so a view only commits while the edit is still in progress.
+    Edit(usize, String),
+    /// Complete every todo, or un-complete them all if they already are.
+    ToggleAll,
+    ClearCompleted,
+    SetFilter(Filter),
+    /// Replace every todo with `n` new ones.
+    Create(usize),
+    /// Append `n` new todos after the existing ones.
+    Append(usize),
+    /// Append `" !!!"` to the text of every `n`th todo.
+    UpdateEvery(usize),
+    /// Swap the todos at two list indices.
+    Swap(usize, usize),
+    /// Highlight a single todo; unknown ids are ignored.
+    Select(usize),
+    /// Remove every todo.
+    Clear,
+}
+
+impl Action {
+    /// Short kebab-case name, used for performance entries.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Add(_) => "add",
+            Action::Toggle(_) => "toggle",
+            Action::Delete(_) => "delete",
+            Action::Edit(..) => "edit",
+            Action::ToggleAll => "toggle-all",
+            Action::ClearCompleted => "clear-completed",
+            Action::SetFilter(_) => "set-filter",
+            Action::Create(_) => "create",
+            Action::Append(_) => "append",
+            Action::UpdateEvery(_) => "update-every",
+            Action::Swap(..) => "swap",
+            Action::Select(_) => "select",
+            Action::Clear => "clear",
+        }
+    }
+
+    /// The js-framework-benchmark style operations, which are not recorded
+    /// in the undo history.
+    pub fn is_benchmark(&self) -> bool {
+        matches!(
+            self,
+            Action::Create(_)
+                | Action::Append(_)
+                | Action::UpdateEvery(_)
+                | Action::Swap(..)
+                | Action::Select(_)
+                | Action::Clear
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TodoState {
+    pub todos: Vec<Todo>,
+    pub filter: Filter,
+    pub next_id: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub selected: Option<usize>,
+}
+
+impl Default for TodoState {
+    fn default() -> Self {
+        Self::seeded(INITIAL_TODO_COUNT)
+    }
+}
+
+impl TodoState {
+    /// Builds `count` todos with ids `1..=count`, every third one completed.
+    pub fn seeded(count: usize) -> Self {
+        let todos = (1..=count)
+            .map(|i| Todo {
+                id: i,
+                text: format!("Todo item {}", i),
+                completed: i % 3 == 0,
+            })
+            .collect();
+
+        Self {
+            todos,
+            filter: Filter::All,
+            next_id: count + 1,
+            selected: None,
+        }
+    }
+
+    fn new_todo(&mut self) -> Todo {
+        let id = self.next_id;
+        self.next_id += 1;
+        Todo {
+            id,
+            text: format!("Todo item {}", id),
+            completed: false,
+        }
+    }
+
+    /// Whether [`apply`](Self::apply) would change the state, without
+    /// applying `action`; for views that must not announce a write that
+    /// turns out to be a no-op.
+    pub fn changes(&self, action: &Action) -> bool {
+        let exists = |id: &usize| self.todos.iter().any(|t| t.id == *id);
+        match action {
+            Action::Add(text) => !text.trim().is_empty(),
+            Action::Toggle(id) | Action::Delete(id) => exists(id),
+            Action::Edit(id, text) => match text.trim() {
+                "" => exists(id),
+                text => self.todos.iter().any(|t| t.id == *id && t.text != text),
+            },
+            Action::ToggleAll | Action::UpdateEvery(_) | Action::Clear => !self.todos.is_empty(),
+            Action::ClearCompleted => self.todos.iter().any(|t| t.completed),
+            Action::SetFilter(filter) => self.filter != *filter,
+            Action::Create(_) => true,
+            Action::Append(count) => *count > 0,
+            Action::Swap(a, b) => a != b && *a.max(b) < self.todos.len(),
+            Action::Select(id) => self.selected != Some(*id) && exists(id),
+        }
+    }
+
+    /// Applies `action` in place and returns whether the state changed.
+    pub fn apply(&mut self, action: Action) -> bool {
+        match action {
+            Action::Add(text) => {
+                if text.trim().is_empty() {
+                    return false;
+                }
+                self.todos.insert(
+                    0,
+                    Todo {
+                        id: self.next_id,
+                        text,
+                        completed: false,
+                    },
+                );
+                self.next_id += 1;
+                true
+            }
+            Action::Toggle(id) => match self.todos.iter_mut().find(|t| t.id == id) {
+                Some(todo) => {
+                    todo.completed = !todo.completed;
+                    true
+                }
+                None => false,
+            },
+            Action::Delete(id) => {
+                let len = self.todos.len();
+                self.todos.retain(|t| t.id != id);
+                if self.selected == Some(id) {
+                    self.selected = None;
+                }
+                self.todos.len() != len
+            }
+            Action::Edit(id, text) => {
+                let text = text.trim();
+                if text.is_empty() {
+                    return self.apply(Action::Delete(id));
+                }
+                match self.todos.iter_mut().find(|t| t.id == id) {
+                    Some(todo) if todo.text != text => {
+                        todo.text = text.to_string();
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Action::ToggleAll => {
+                if self.todos.is_empty() {
+                    return false;
+                }
+                let completed = !self.all_completed();
+                for todo in &mut self.todos {
+                    todo.completed = completed;
+                }
+                true
+            }
+            Action::ClearCompleted => {
+                let len = self.todos.len();
+                self.todos.retain(|t| !t.completed);
+                if let Some(id) = self.selected {
+                    if !self.todos.iter().any(|t| t.id == id) {
+                        self.selected = None;
+                    }
+                }
+                self.todos.len() != len
+            }
+            Action::SetFilter(filter) => {
+                let changed = self.filter != filter;
+                self.filter = filter;
+                changed
+            }
+            Action::Create(count) => {
+                self.todos = (0..count).map(|_| self.new_todo()).collect();
+                self.selected = None;
+                true
+            }
+            Action::Append(count) => {
+                self.todos.reserve(count);
+                for _ in 0..count {
+                    let todo = self.new_todo();
+                    self.todos.push(todo);
+                }
+                count > 0
+            }
+            Action::UpdateEvery(step) => {
+                for todo in self.todos.iter_mut().step_by(step.max(1)) {
+                    todo.text.push_str(" !!!");
+                }
+                !self.todos.is_empty()
+            }
+            Action::Swap(a, b) => {
+                if a == b || a.max(b) >= self.todos.len() {
+                    return false;
+                }
+                self.todos.swap(a, b);
+                true
+            }
+            Action::Select(id) => {
+                if self.selected == Some(id) || !self.todos.iter().any(|t| t.id == id) {
+                    return false;
+                }
+                self.selected = Some(id);
+                true
+            }
+            Action::Clear => {
+                let changed = !self.todos.is_empty();
+                self.todos.clear();
+                self.selected = None;
+                changed
+            }
+        }
+    }
+
+    /// Todos visible under the current filter, in display order.
+    pub fn filtered(&self) -> impl Iterator<Item = &Todo> + '_ {
+        self.todos.iter().filter(move |todo| self.filter.matches(todo))
+    }
+
+    pub fn filtered_todos(&self) -> Vec<Todo> {
+        self.filtered().cloned().collect()
+    }
+
+    pub fn remaining_count(&self) -> usize {
+        self.todos.iter().filter(|t| !t.completed).count()
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.todos.len() - self.remaining_count()
+    }
+
+    /// Whether the toggle-all checkbox is checked (false for an empty list).
+    pub fn all_completed(&self) -> bool {
+        !self.todos.is_empty() && self.todos.iter().all(|t| t.completed)
+    }
+}
+
+/// The "N item(s) remaining" label shown in `.todo-stats`.
+pub fn remaining_label(count: usize) -> String {
+    format!("{} {} remaining", count, if count == 1 { "item" } else { "items" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(state: &TodoState) -> Vec<&str> {
+        state.todos.iter().map(|t| t.text.as_str()).collect()
+    }
+
+    #[test]
+    fn changes_predicts_apply() {
+        let mut state = TodoState::seeded(3);
+        state.apply(Action::Select(1));
+        let actions = [
+            Action::Add("New".to_string()),
+            Action::Add(" ".to_string()),
+            Action::Toggle(2),
+            Action::Toggle(42),
+            Action::Delete(42),
+            Action::Edit(1, " Todo item 1 ".to_string()),
+            Action::Edit(1, "Renamed".to_string()),
+            Action::Edit(42, "".to_string()),
+            Action::ToggleAll,
+            Action::ClearCompleted,
+            Action::SetFilter(Filter::All),
+            Action::SetFilter(Filter::Active),
+            Action::Create(0),
+            Action::Append(0),
+            Action::Append(2),
+            Action::UpdateEvery(2),
+            Action::Swap(0, 2),
+            Action::Swap(1, 1),
+            Action::Swap(0, 3),
+            Action::Select(1),
+            Action::Select(2),
+            Action::Select(42),
+            Action::Clear,
+        ];
+        for action in actions {
+            let expected = state.changes(&action);
+            assert_eq!(state.clone().apply(action.clone()), expected, "{:?}", action);
+        }
+        for action in [Action::ToggleAll, Action::UpdateEvery(1), Action::Clear] {
+            assert!(!TodoState::seeded(0).changes(&action), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn add_prepends_and_ignores_blank_text() {
+        let mut state = TodoState::seeded(2);
+        assert!(state.apply(Action::Add("Write tests".to_string())));
+        assert_eq!(state.todos[0].id, 3);
+        assert_eq!(state.todos[0].text, "Write tests");
+        assert_eq!(state.next_id, 4);
+
+        assert!(!state.apply(Action::Add(" \t".to_string())));
+        assert_eq!(state.todos.len(), 3);
+        assert_eq!(state.next_id, 4);
+    }
+
+    #[test]
+    fn toggle_and_delete_ignore_unknown_ids() {
+        let mut state = TodoState::seeded(3);
+        assert!(state.apply(Action::Toggle(1)));
+        assert!(state.todos[0].completed);
+        assert!(!state.apply(Action::Toggle(42)));
+
+        assert!(state.apply(Action::Delete(2)));
+        assert_eq!(texts(&state), ["Todo item 1", "Todo item 3"]);
+        assert!(!state.apply(Action::Delete(2)));
+    }
+
+    #[test]
+    fn edit_trims_and_deletes_when_empty() {
+        let mut state = TodoState::seeded(2);
+        assert!(state.apply(Action::Edit(1, "  Renamed ".to_string())));
+        assert_eq!(state.todos[0].text, "Renamed");
+        assert!(!state.apply(Action::Edit(1, "Renamed".to_string())));
+        assert!(!state.apply(Action::Edit(42, "Missing".to_string())));
+
+        assert!(state.apply(Action::Edit(1, "   ".to_string())));
+        assert_eq!(texts(&state), ["Todo item 2"]);
+    }
+
+    #[test]
+    fn toggle_all_completes_then_clears() {
+        let mut state = TodoState::seeded(3);
+        assert!(state.apply(Action::ToggleAll));
+        assert!(state.all_completed());
+        assert!(state.apply(Action::ToggleAll));
+        assert_eq!(state.remaining_count(), 3);
+
+        assert!(!TodoState::seeded(0).apply(Action::ToggleAll));
+    }
+
+    #[test]
+    fn clear_completed_removes_completed_todos() {
+        let mut state = TodoState::seeded(6);
+        state.apply(Action::Select(3));
+        assert!(state.apply(Action::ClearCompleted));
+        assert_eq!(state.completed_count(), 0);
+        assert_eq!(state.todos.len(), 4);
+        assert_eq!(state.selected, None);
+        assert!(!state.apply(Action::ClearCompleted));
+    }
+
+    #[test]
+    fn set_filter_reports_changes() {
+        let mut state = TodoState::seeded(3);
+        assert!(!state.apply(Action::SetFilter(Filter::All)));
+        assert!(state.apply(Action::SetFilter(Filter::Completed)));
+        assert_eq!(state.filtered_todos().len(), 1);
+    }
+
+    #[test]
+    fn create_and_append_use_fresh_ids() {
+        let mut state = TodoState::seeded(2);
+        state.apply(Action::Select(1));
+        assert!(state.apply(Action::Create(2)));
+        assert_eq!(texts(&state), ["Todo item 3", "Todo item 4"]);
+        assert_eq!(state.selected, None);
+
+        assert!(state.apply(Action::Append(1)));
+        assert_eq!(state.todos[2].id, 5);
+        assert!(!state.apply(Action::Append(0)));
+        assert_eq!(state.next_id, 6);
+    }
+
+    #[test]
+    fn update_every_marks_every_nth_todo() {
+        let mut state = TodoState::seeded(4);
+        assert!(state.apply(Action::UpdateEvery(2)));
+        assert_eq!(
+            texts(&state),
+            [
+                "Todo item 1 !!!",
+                "Todo item 2",
+                "Todo item 3 !!!",
+                "Todo item 4"
+            ]
+        );
+        assert!(!TodoState::seeded(0).apply(Action::UpdateEvery(2)));
+    }
+
+    #[test]
+    fn swap_needs_two_valid_indices() {
+        let mut state = TodoState::seeded(3);
+        assert!(state.apply(Action::Swap(0, 2)));
+        assert_eq!(texts(&state), ["Todo item 3", "Todo item 2", "Todo item 1"]);
+        assert!(!state.apply(Action::Swap(1, 1)));
+        assert!(!state.apply(Action::Swap(0, 3)));
+    }
+
+    #[test]
+    fn select_ignores_unknown_ids() {
+        let mut state = TodoState::seeded(3);
+        assert!(state.apply(Action::Select(2)));
+        assert!(!state.apply(Action::Select(2)));
+        assert!(!state.apply(Action::Select(42)));
+        assert_eq!(state.selected, Some(2));
+
+        assert!(state.apply(Action::Delete(2)));
+        assert_eq!(state.selected, None);
+    }
+
+    #[test]
+    fn clear_removes_everything() {
+        let mut state = TodoState::seeded(3);
+        state.apply(Action::Select(1));
+        assert!(state.apply(Action::Clear));
+        assert!(state.todos.is_empty());
+        assert_eq!(state.selected, None);
+        assert!(!state.apply(Action::Clear));
+    }
+}
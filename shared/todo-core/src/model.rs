@@ -0,0 +1,268 @@
+//! Property-based model tests of the todo state transitions
+//! (`--features model`).
+//!
+//! [`actions`] generates random sequences of the list actions: add and edit
+//! (including whitespace-only text), toggle, delete, toggle all, clear
+//! completed and set filter. [`check`] runs one through
+//! an [`Implementation`] and through [`Model`], a deliberately naive
+//! reference, and compares them after every step: the visible list, the
+//! remaining count, unique ids and a `next_id` that never goes back.
+//!
+//! `TodoState` and `History` are checked here; an app with state logic of
+//! its own implements [`Implementation`] and runs the same sequences.
+
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+
+use crate::history::History;
+use crate::{Action, Filter, Todo, TodoState};
+
+/// Ids are drawn from a range a little above what a sequence can reach, so
+/// toggles, deletes and edits hit both existing and missing todos.
+const MAX_ID: usize = 48;
+
+/// State logic under test.
+pub trait Implementation {
+    fn apply(&mut self, action: Action);
+    fn state(&self) -> TodoState;
+}
+
+impl Implementation for TodoState {
+    fn apply(&mut self, action: Action) {
+        TodoState::apply(self, action);
+    }
+
+    fn state(&self) -> TodoState {
+        self.clone()
+    }
+}
+
+/// The path every app dispatches through: [`History::apply`] on its state.
+impl Implementation for (History, TodoState) {
+    fn apply(&mut self, action: Action) {
+        let (history, state) = self;
+        history.apply(state, action);
+    }
+
+    fn state(&self) -> TodoState {
+        self.1.clone()
+    }
+}
+
+/// The reference the implementations are compared with.
+#[derive(Clone, Debug)]
+pub struct Model {
+    /// Newest first.
+    pub todos: Vec<Todo>,
+    pub filter: Filter,
+    pub next_id: usize,
+}
+
+impl Model {
+    pub fn new(initial: &TodoState) -> Self {
+        Self {
+            todos: initial.todos.clone(),
+            filter: initial.filter,
+            next_id: initial.next_id,
+        }
+    }
+
+    pub fn apply(&mut self, action: &Action) {
+        match action {
+            Action::Add(text) if text.chars().all(char::is_whitespace) => {}
+            Action::Add(text) => {
+                let todo = Todo {
+                    id: self.next_id,
+                    text: text.clone(),
+                    completed: false,
+                };
+                self.todos.insert(0, todo);
+                self.next_id += 1;
+            }
+            Action::Toggle(id) => {
+                for todo in &mut self.todos {
+                    if todo.id == *id {
+                        todo.completed = !todo.completed;
+                    }
+                }
+            }
+            Action::Delete(id) => self.todos.retain(|todo| todo.id != *id),
+            Action::Edit(id, text) if text.trim().is_empty() => {
+                self.todos.retain(|todo| todo.id != *id)
+            }
+            Action::Edit(id, text) => {
+                for todo in &mut self.todos {
+                    if todo.id == *id {
+                        todo.text = text.trim().to_string();
+                    }
+                }
+            }
+            Action::ToggleAll => {
+                let completed = self.todos.iter().any(|todo| !todo.completed);
+                for todo in &mut self.todos {
+                    todo.completed = completed;
+                }
+            }
+            Action::ClearCompleted => self.todos.retain(|todo| !todo.completed),
+            Action::SetFilter(filter) => self.filter = *filter,
+            Action::Create(count) => {
+                self.todos.clear();
+                self.push(*count);
+            }
+            Action::Append(count) => self.push(*count),
+            Action::UpdateEvery(step) => {
+                for (index, todo) in self.todos.iter_mut().enumerate() {
+                    if index % (*step).max(1) == 0 {
+                        todo.text += " !!!";
+                    }
+                }
+            }
+            Action::Swap(a, b) if *a < self.todos.len() && *b < self.todos.len() => {
+                self.todos.swap(*a, *b)
+            }
+            Action::Swap(..) | Action::Select(_) => {}
+            Action::Clear => self.todos.clear(),
+        }
+    }
+
+    /// Appends `count` todos as the benchmark operations name them.
+    fn push(&mut self, count: usize) {
+        for _ in 0..count {
+            self.todos.push(Todo {
+                id: self.next_id,
+                text: format!("Todo item {}", self.next_id),
+                completed: false,
+            });
+            self.next_id += 1;
+        }
+    }
+
+    pub fn visible(&self) -> Vec<Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| match self.filter {
+                Filter::All => true,
+                Filter::Active => !todo.completed,
+                Filter::Completed => todo.completed,
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.todos.iter().filter(|todo| !todo.completed).count()
+    }
+}
+
+/// A short seeded list to start from.
+pub fn initial_state() -> impl Strategy<Value = TodoState> {
+    (0..8usize).prop_map(TodoState::seeded)
+}
+
+/// Add and edit text: arbitrary, whitespace-only or a few plain words.
+pub fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<String>(),
+        "[ \t\n\r]{0,4}",
+        "[a-z]{1,8}( [a-z]{1,8}){0,3}"
+    ]
+}
+
+pub fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        text().prop_map(Action::Add),
+        (0..MAX_ID).prop_map(Action::Toggle),
+        (0..MAX_ID).prop_map(Action::Delete),
+        (0..MAX_ID, text()).prop_map(|(id, text)| Action::Edit(id, text)),
+        Just(Action::ToggleAll),
+        Just(Action::ClearCompleted),
+        prop_oneof![
+            Just(Filter::All),
+            Just(Filter::Active),
+            Just(Filter::Completed)
+        ]
+        .prop_map(Action::SetFilter),
+    ]
+}
+
+pub fn actions() -> impl Strategy<Value = Vec<Action>> {
+    prop::collection::vec(action(), 0..40)
+}
+
+/// Applies `actions` to `implementation`, which starts from `initial`, and
+/// to the model, failing at the first step where they disagree.
+pub fn check(
+    initial: &TodoState,
+    implementation: &mut impl Implementation,
+    actions: &[Action],
+) -> Result<(), TestCaseError> {
+    let mut model = Model::new(initial);
+    let mut next_id = initial.next_id;
+
+    for (step, action) in actions.iter().enumerate() {
+        implementation.apply(action.clone());
+        model.apply(action);
+        let state = implementation.state();
+
+        prop_assert_eq!(
+            state.filtered_todos(),
+            model.visible(),
+            "visible list after step {} ({:?})",
+            step,
+            action
+        );
+        prop_assert_eq!(
+            state.remaining_count(),
+            model.remaining(),
+            "remaining count after step {} ({:?})",
+            step,
+            action
+        );
+
+        let mut ids = HashSet::new();
+        prop_assert!(
+            state.todos.iter().all(|todo| ids.insert(todo.id)),
+            "duplicate id after step {} ({:?})",
+            step,
+            action
+        );
+        prop_assert!(
+            state.next_id >= next_id && state.todos.iter().all(|todo| todo.id < state.next_id),
+            "next_id went from {} to {} after step {} ({:?})",
+            next_id,
+            state.next_id,
+            step,
+            action
+        );
+        next_id = state.next_id;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn todo_state_matches_model(initial in initial_state(), actions in actions()) {
+            check(&initial, &mut initial.clone(), &actions)?;
+        }
+
+        #[test]
+        fn changes_predicts_apply(initial in initial_state(), actions in actions()) {
+            let mut state = initial;
+            for action in actions {
+                let expected = state.changes(&action);
+                prop_assert_eq!(state.apply(action.clone()), expected, "{:?}", action);
+            }
+        }
+
+        #[test]
+        fn history_matches_model(initial in initial_state(), actions in actions()) {
+            check(&initial, &mut (History::default(), initial.clone()), &actions)?;
+        }
+    }
+}
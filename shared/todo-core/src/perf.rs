@@ -0,0 +1,77 @@
+//! `performance.mark` / `performance.measure` entries around state updates.
+//!
+//! Every update is bracketed by `todo:<name>:start` and `todo:<name>:end`
+//! marks, where `<name>` is [`crate::Action::name`] (or `undo` / `redo`), and
+//! measured as `todo:<name>`. The end mark is set once the app has
+//! rendered the change, so the measure covers update-to-DOM time in every
+//! implementation alike. Builds that store an update on a server first
+//! measure that call separately as `todo:<name>:server`.
+
+use std::future::Future;
+
+use web_sys::Performance;
+
+/// Updates that have started but whose render has not finished yet.
+#[derive(Clone, Debug, Default)]
+pub struct UpdateMarks {
+    pending: Vec<&'static str>,
+}
+
+impl UpdateMarks {
+    /// Marks the start of `name`, runs `update` and returns its result.
+    ///
+    /// An update that changed nothing will not be rendered, so it is ended
+    /// right away instead of waiting for [`UpdateMarks::rendered`].
+    pub fn measure(&mut self, name: &'static str, update: impl FnOnce() -> bool) -> bool {
+        mark(name, "start");
+        let changed = update();
+        if changed {
+            self.pending.push(name);
+        } else {
+            end(name);
+        }
+        changed
+    }
+
+    /// Ends every pending update; call it after the app has rendered.
+    pub fn rendered(&mut self) {
+        for name in self.pending.drain(..) {
+            end(name);
+        }
+    }
+}
+
+/// Awaits `call`, the server round trip that precedes update `name`,
+/// between `todo:<name>:server-start` and `todo:<name>:server-end` marks.
+pub async fn measure_server<T>(name: &'static str, call: impl Future<Output = T>) -> T {
+    mark(name, "server-start");
+    let output = call.await;
+    mark(name, "server-end");
+    measure(&format!("{}:server", name), name, "server-start", "server-end");
+    output
+}
+
+fn performance() -> Option<Performance> {
+    crate::web::window()?.performance()
+}
+
+fn mark(name: &str, phase: &str) {
+    if let Some(performance) = performance() {
+        let _ = performance.mark(&format!("todo:{}:{}", name, phase));
+    }
+}
+
+fn end(name: &str) {
+    mark(name, "end");
+    measure(name, name, "start", "end");
+}
+
+fn measure(measure: &str, name: &str, start: &str, end: &str) {
+    if let Some(performance) = performance() {
+        let _ = performance.measure_with_start_mark_and_end_mark(
+            &format!("todo:{}", measure),
+            &format!("todo:{}:{}", name, start),
+            &format!("todo:{}:{}", name, end),
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! UI state a native render starts in, so the markup of a selected row, an
+//! open edit field and the virtualized list can be checked without a
+//! browser.
+//!
+//! Only the native renderers read it, from `selected=<id>`, `editing=<id>`
+//! and `virtual=1` arguments next to the [`SeedConfig`](crate::seed::SeedConfig)
+//! ones: `cargo run -- count=1000 seed=42 editing=3` in an app prints the
+//! markup it renders. The browser builds never start in a preview state, so
+//! the module is left out of wasm builds altogether.
+
+use crate::seed::{arg, parse};
+use crate::viewport::Viewport;
+use crate::{Action, TodoState};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Preview {
+    /// Todo to highlight, as [`Action::Select`] does.
+    pub selected: Option<usize>,
+    /// Todo whose edit field is open.
+    pub editing: Option<usize>,
+    /// Whether the list is virtualized, as with `?virtual=1`.
+    pub virtualized: bool,
+}
+
+impl Preview {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let args: Vec<String> = args.into_iter().collect();
+        let param = |name: &str| arg(&args, name);
+        Self {
+            selected: parse(&param, "selected"),
+            editing: parse(&param, "editing"),
+            virtualized: matches!(param("virtual").as_deref(), Some("" | "1" | "true")),
+        }
+    }
+
+    /// Applies the preview to what a page starts with: selects in the
+    /// seeded `state` and virtualizes the list unless `viewport` already
+    /// is. Also returns the id and text of the todo whose edit field starts
+    /// open, if it exists.
+    pub fn start(
+        &self,
+        mut state: TodoState,
+        viewport: Option<Viewport>,
+    ) -> (TodoState, Option<Viewport>, Option<(usize, String)>) {
+        if let Some(id) = self.selected {
+            state.apply(Action::Select(id));
+        }
+        let viewport = viewport.or_else(|| self.virtualized.then(Viewport::default));
+        let edited = self
+            .editing
+            .and_then(|id| state.todos.iter().find(|todo| todo.id == id))
+            .map(|todo| (todo.id, todo.text.clone()));
+        (state, viewport, edited)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::SeedConfig;
+
+    #[test]
+    fn reads_the_preview_state() {
+        let args = ["count=3", "selected=2", "editing=x", "virtual=1"].map(String::from);
+        let preview = Preview::from_args(args.clone());
+        assert_eq!(
+            preview,
+            Preview {
+                selected: Some(2),
+                editing: None,
+                virtualized: true,
+            }
+        );
+        assert_eq!(Preview::from_args(Vec::new()), Preview::default());
+    }
+
+    #[test]
+    fn starts_the_page_in_the_preview_state() {
+        let state = SeedConfig::from_args(["count=3".to_string()]).generate();
+        let preview = Preview {
+            selected: Some(2),
+            editing: Some(3),
+            virtualized: true,
+        };
+        let (started, viewport, edited) = preview.start(state.clone(), None);
+        assert_eq!(started.selected, Some(2));
+        assert_eq!(viewport, Some(Viewport::default()));
+        assert_eq!(edited, Some((3, state.todos[2].text.clone())));
+
+        let missing = Preview {
+            editing: Some(9),
+            ..Preview::default()
+        };
+        assert_eq!(missing.start(state.clone(), None), (state, None, None));
+    }
+}
@@ -0,0 +1,212 @@
+//! Deterministic generation of the initial todo list.
+//!
+//! The same [`SeedConfig`] yields byte-identical todos in every
+//! implementation, so scaling runs (`?count=100` up to `?count=100000`)
+//! compare like with like. The generator is a local SplitMix64 rather than
+//! a `rand` dependency so its output can never change under us.
+
+use crate::{Todo, TodoState, INITIAL_TODO_COUNT};
+
+/// Upper bound on `count`, so a typo cannot hang the tab.
+pub const MAX_SEED_COUNT: usize = 1_000_000;
+
+/// Upper bound on `textLen`, for the same reason.
+pub const MAX_TEXT_LEN: usize = 1_000;
+
+const WORDS: &[&str] = &[
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet",
+    "kilo", "lima", "mike", "november", "oscar", "papa", "quebec", "romeo", "sierra", "tango",
+    "uniform", "victor", "whiskey", "xray", "yankee", "zulu",
+];
+
+/// Shape of the initial list, read from `?count`, `?seed`,
+/// `?completedRatio` and `?textLen`.
+///
+/// Without `completedRatio` every third todo is completed, and without
+/// `textLen` todos are named `"Todo item {id}"`, so the defaults reproduce
+/// the original 100-item list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeedConfig {
+    pub count: usize,
+    pub seed: u64,
+    /// Probability in `0.0..=1.0` that a todo starts completed.
+    pub completed_ratio: Option<f64>,
+    /// Exact length in characters of every todo's text.
+    pub text_len: Option<usize>,
+}
+
+impl Default for SeedConfig {
+    fn default() -> Self {
+        Self {
+            count: INITIAL_TODO_COUNT,
+            seed: 1,
+            completed_ratio: None,
+            text_len: None,
+        }
+    }
+}
+
+impl SeedConfig {
+    /// Reads the parameters through `param`; missing or unparsable values
+    /// keep their defaults and out-of-range ones are clamped.
+    pub fn from_params(param: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        Self {
+            count: parse(&param, "count")
+                .map_or(defaults.count, |count: usize| count.min(MAX_SEED_COUNT)),
+            seed: parse(&param, "seed").unwrap_or(defaults.seed),
+            completed_ratio: parse(&param, "completedRatio")
+                .filter(|ratio: &f64| ratio.is_finite())
+                .map(|ratio| ratio.clamp(0.0, 1.0)),
+            text_len: parse(&param, "textLen").map(|len: usize| len.min(MAX_TEXT_LEN)),
+        }
+    }
+
+    /// Reads `name=value` arguments, as given to the native renderers
+    /// (`cargo run -- count=1000 seed=42`).
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let args: Vec<String> = args.into_iter().collect();
+        Self::from_params(|name| arg(&args, name))
+    }
+
+    pub fn generate(&self) -> TodoState {
+        let mut rng = SplitMix64(self.seed);
+        let todos = (1..=self.count)
+            .map(|id| {
+                let completed = match self.completed_ratio {
+                    Some(ratio) => rng.next_f64() < ratio,
+                    None => id % 3 == 0,
+                };
+                let text = match self.text_len {
+                    Some(len) => text_of_len(&mut rng, id, len),
+                    None => format!("Todo item {}", id),
+                };
+                Todo { id, text, completed }
+            })
+            .collect();
+
+        let mut state = TodoState::seeded(0);
+        state.todos = todos;
+        state.next_id = self.count + 1;
+        state
+    }
+}
+
+pub(crate) fn parse<T: std::str::FromStr>(param: &impl Fn(&str) -> Option<String>, name: &str) -> Option<T> {
+    param(name)?.trim().parse().ok()
+}
+
+/// The value of a `name=value` argument.
+pub(crate) fn arg(args: &[String], name: &str) -> Option<String> {
+    args.iter().find_map(|arg| {
+        arg.strip_prefix(name)?
+            .strip_prefix('=')
+            .map(str::to_string)
+    })
+}
+
+/// `"Todo item {id}"` followed by random words, cut to exactly `len` chars.
+fn text_of_len(rng: &mut SplitMix64, id: usize, len: usize) -> String {
+    let mut text = format!("Todo item {}", id);
+    while text.len() < len {
+        text.push(' ');
+        text.push_str(WORDS[rng.next_u64() as usize % WORDS.len()]);
+    }
+    text.truncate(len);
+    text
+}
+
+/// SplitMix64 (Steele, Lea and Flood), small and fully specified.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0.0..1.0` from the top 53 bits.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(params: &[(&str, &str)]) -> SeedConfig {
+        SeedConfig::from_params(|name| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn defaults_reproduce_the_seeded_list() {
+        assert_eq!(config(&[]), SeedConfig::default());
+        assert_eq!(
+            SeedConfig::default().generate(),
+            TodoState::seeded(INITIAL_TODO_COUNT)
+        );
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_list() {
+        let params = [
+            ("count", "500"),
+            ("seed", "42"),
+            ("completedRatio", "0.3"),
+            ("textLen", "40"),
+        ];
+        let state = config(&params).generate();
+        assert_eq!(state, config(&params).generate());
+        assert_eq!(state.next_id, 501);
+        assert!(state.todos.iter().all(|todo| todo.text.len() == 40));
+
+        let mut other = params;
+        other[1] = ("seed", "43");
+        assert_ne!(state, config(&other).generate());
+    }
+
+    #[test]
+    fn completed_ratio_sets_the_share_of_completed_todos() {
+        let completed = |ratio: &str| {
+            config(&[("count", "10000"), ("completedRatio", ratio)])
+                .generate()
+                .completed_count()
+        };
+        assert_eq!(completed("0"), 0);
+        assert_eq!(completed("1"), 10_000);
+        assert!((4_800..5_200).contains(&completed("0.5")));
+    }
+
+    #[test]
+    fn clamps_out_of_range_values() {
+        let clamped = config(&[
+            ("count", "1000000000"),
+            ("completedRatio", "7"),
+            ("textLen", "1000000000"),
+        ]);
+        assert_eq!(clamped.count, MAX_SEED_COUNT);
+        assert_eq!(clamped.completed_ratio, Some(1.0));
+        assert_eq!(clamped.text_len, Some(MAX_TEXT_LEN));
+
+        let invalid = config(&[("count", "-1"), ("completedRatio", "NaN")]);
+        assert_eq!(invalid.count, INITIAL_TODO_COUNT);
+        assert_eq!(invalid.completed_ratio, None);
+    }
+
+    #[test]
+    fn reads_name_value_arguments() {
+        let args = ["count=3", "seed=9", "textLen=5"].map(String::from);
+        let config = SeedConfig::from_args(args);
+        assert_eq!((config.count, config.seed), (3, 9));
+        assert_eq!(config.generate().todos[0].text, "Todo ");
+    }
+}
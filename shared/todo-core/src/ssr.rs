@@ -0,0 +1,99 @@
+//! The axum server of the SSR + hydration builds (`--features ssr` in the
+//! Yew and Leptos apps).
+//!
+//! Every page request renders the list sized by its query parameters and
+//! streams it into the shell that `trunk build index.hydrate.html`
+//! produces; the client built with `--features hydrate` then hydrates it in
+//! place instead of rendering it again. Everything else is served from the
+//! Trunk output and the shared stylesheet directory. The apps only supply
+//! the render call.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{OriginalUri, Query};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures::stream::{self, Stream, StreamExt};
+use tower_http::services::ServeDir;
+
+use crate::seed::SeedConfig;
+
+/// The Trunk output, split around the empty `<body>` the app renders into.
+struct Shell {
+    head: String,
+    tail: String,
+}
+
+impl Shell {
+    fn load(site_root: &str) -> std::io::Result<Self> {
+        let path = format!("{}/index.html", site_root);
+        let html = std::fs::read_to_string(&path)?;
+        let (head, rest) = html.split_once("<body>").ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("no <body> in {}", path),
+            )
+        })?;
+        let (_, tail) = rest.split_once("</body>").unwrap_or(("", rest));
+        Ok(Self {
+            head: format!("{}<body>", head),
+            tail: format!("</body>{}", tail),
+        })
+    }
+}
+
+/// Serves `framework` on `LISTEN_ADDR` (default `127.0.0.1:3000`), reading
+/// the client from `SITE_ROOT` (default `dist-hydrate`) and the stylesheet
+/// from `SHARED_ROOT` (default `../../shared`).
+///
+/// `render` streams the app's hydratable markup for a seed and the request
+/// URL, path and query.
+pub async fn serve<R, S>(framework: &str, render: R) -> std::io::Result<()>
+where
+    R: Fn(SeedConfig, String) -> S + Send + Sync + 'static,
+    S: Stream<Item = String> + Send + 'static,
+{
+    let addr = std::env::var("LISTEN_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".into());
+    let site_root = std::env::var("SITE_ROOT").unwrap_or_else(|_| "dist-hydrate".into());
+    let shared_root = std::env::var("SHARED_ROOT").unwrap_or_else(|_| "../../shared".into());
+
+    let shell = Arc::new(Shell::load(&site_root)?);
+    let render = Arc::new(render);
+    let page = move |OriginalUri(uri): OriginalUri,
+                     Query(params): Query<HashMap<String, String>>| {
+        let (shell, render) = (shell.clone(), render.clone());
+        async move {
+            let seed = SeedConfig::from_params(|name| params.get(name).cloned());
+            respond(&shell, render(seed, uri.to_string()))
+        }
+    };
+
+    let app = Router::new()
+        .route("/", get(page.clone()))
+        .route("/index.html", get(page))
+        .nest_service("/shared", ServeDir::new(shared_root))
+        .fallback_service(ServeDir::new(site_root));
+
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    println!("{} SSR listening on http://{}", framework, addr);
+    axum::serve(listener, app).await
+}
+
+/// The shell with the app's markup streamed into its `<body>`.
+fn respond(shell: &Shell, app: impl Stream<Item = String> + Send + 'static) -> Response {
+    let html = stream::once(futures::future::ready(shell.head.clone()))
+        .chain(app)
+        .chain(stream::once(futures::future::ready(shell.tail.clone())))
+        .map(Ok::<_, Infallible>);
+
+    (
+        [(CONTENT_TYPE, "text/html; charset=utf-8")],
+        Body::from_stream(html),
+    )
+        .into_response()
+}
@@ -0,0 +1,155 @@
+//! Versioned JSON encoding of [`TodoState`] for persistence.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::TodoState;
+
+/// Version written by [`encode`]; bump it whenever the stored shape changes.
+pub const SCHEMA_VERSION: u64 = 1;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Json(serde_json::Error),
+    MissingVersion,
+    UnsupportedVersion(u64),
+    DuplicateId(usize),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Json(err) => write!(f, "invalid stored todos: {}", err),
+            StorageError::MissingVersion => write!(f, "stored todos have no schema version"),
+            StorageError::UnsupportedVersion(version) => {
+                write!(f, "unsupported stored todos schema version {}", version)
+            }
+            StorageError::DuplicateId(id) => write!(f, "stored todos contain duplicate id {}", id),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(err: serde_json::Error) -> Self {
+        StorageError::Json(err)
+    }
+}
+
+#[derive(Serialize)]
+struct Stored<'a> {
+    version: u64,
+    #[serde(flatten)]
+    state: &'a TodoState,
+}
+
+pub fn encode(state: &TodoState) -> String {
+    serde_json::to_string(&Stored {
+        version: SCHEMA_VERSION,
+        state,
+    })
+    .expect("TodoState serializes to JSON")
+}
+
+/// Parses stored data of any supported version into the current state.
+pub fn decode(json: &str) -> Result<TodoState, StorageError> {
+    let value: Value = serde_json::from_str(json)?;
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or(StorageError::MissingVersion)?;
+
+    let mut state: TodoState = serde_json::from_value(migrate(value, version)?)?;
+
+    let mut ids = HashSet::with_capacity(state.todos.len());
+    for todo in &state.todos {
+        if !ids.insert(todo.id) {
+            return Err(StorageError::DuplicateId(todo.id));
+        }
+    }
+    // Never hand out an id that is already taken, even if the stored
+    // counter was edited by hand.
+    let max_id = state.todos.iter().map(|t| t.id).max().unwrap_or(0);
+    state.next_id = state.next_id.max(max_id + 1);
+
+    Ok(state)
+}
+
+/// Upgrades `value` from `version` to [`SCHEMA_VERSION`].
+///
+/// A schema change adds an arm here that rewrites the previous version's
+/// shape and recurses with `version + 1`, so data of any older version is
+/// migrated step by step.
+fn migrate(value: Value, version: u64) -> Result<Value, StorageError> {
+    match version {
+        SCHEMA_VERSION => Ok(value),
+        other => Err(StorageError::UnsupportedVersion(other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, Filter};
+
+    #[test]
+    fn round_trips_state() {
+        let mut state = TodoState::seeded(3);
+        state.apply(Action::Toggle(1));
+        state.apply(Action::SetFilter(Filter::Active));
+        state.apply(Action::Select(2));
+
+        let json = encode(&state);
+        assert!(json.contains(&format!("\"version\":{}", SCHEMA_VERSION)));
+        assert_eq!(decode(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn rejects_missing_and_unknown_versions() {
+        let json = encode(&TodoState::seeded(1));
+        let unversioned = json.replace(&format!("\"version\":{},", SCHEMA_VERSION), "");
+        assert!(matches!(
+            decode(&unversioned),
+            Err(StorageError::MissingVersion)
+        ));
+
+        let newer = SCHEMA_VERSION + 1;
+        let json = json.replace(
+            &format!("\"version\":{}", SCHEMA_VERSION),
+            &format!("\"version\":{}", newer),
+        );
+        assert!(matches!(
+            migrate(serde_json::from_str(&json).unwrap(), newer),
+            Err(StorageError::UnsupportedVersion(version)) if version == newer
+        ));
+        assert!(matches!(
+            decode(&json),
+            Err(StorageError::UnsupportedVersion(version)) if version == newer
+        ));
+        assert!(matches!(decode("{"), Err(StorageError::Json(_))));
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let mut state = TodoState::seeded(2);
+        state.todos[1].id = 1;
+        assert!(matches!(
+            decode(&encode(&state)),
+            Err(StorageError::DuplicateId(1))
+        ));
+    }
+
+    #[test]
+    fn bumps_next_id_past_stored_ids() {
+        let mut state = TodoState::seeded(5);
+        state.next_id = 2;
+        assert_eq!(decode(&encode(&state)).unwrap().next_id, 6);
+
+        state.next_id = 10;
+        assert_eq!(decode(&encode(&state)).unwrap().next_id, 10);
+    }
+}
@@ -0,0 +1,146 @@
+//! Windowing for the optional virtualized list (`?virtual=1`).
+//!
+//! Only the rows that intersect the scroll container, plus [`OVERSCAN`]
+//! rows on either side, are rendered; two spacer rows stand in for the
+//! rest so the scrollbar keeps the full list's height.
+
+/// Row pitch in pixels when `--todo-row-height` cannot be read.
+pub const DEFAULT_ROW_HEIGHT: i32 = 70;
+
+/// Height of `.todo-list.virtual` before it has been measured.
+pub const DEFAULT_VIEWPORT_HEIGHT: i32 = 400;
+
+/// Rows rendered beyond each edge of the visible area.
+pub const OVERSCAN: usize = 5;
+
+/// The scroll container's geometry, all in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    /// Height of one row including its margin.
+    pub row_height: i32,
+    pub height: i32,
+    pub scroll_top: i32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            row_height: DEFAULT_ROW_HEIGHT,
+            height: DEFAULT_VIEWPORT_HEIGHT,
+            scroll_top: 0,
+        }
+    }
+}
+
+/// The slice of rows to render and the spacer heights around it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VisibleRange {
+    pub start: usize,
+    pub end: usize,
+    pub padding_top: usize,
+    pub padding_bottom: usize,
+    /// `scroll_top` clamped to the list's current height.
+    ///
+    /// A list element recreated after the empty state starts at the top,
+    /// so views scroll it back here and a filter change keeps the position.
+    pub scroll_top: i32,
+}
+
+impl VisibleRange {
+    /// Every row, for the non-virtualized list.
+    pub fn all(total: usize) -> Self {
+        Self {
+            end: total,
+            ..Self::default()
+        }
+    }
+}
+
+impl Viewport {
+    /// Rows to render out of `total` at the current scroll position.
+    ///
+    /// The scroll position is clamped first, so a filter that shortens the
+    /// list keeps the window inside it instead of rendering nothing.
+    pub fn visible(&self, total: usize) -> VisibleRange {
+        let row_height = self.row_height.max(1) as usize;
+        let height = self.height.max(0) as usize;
+        let max_scroll = (total * row_height).saturating_sub(height);
+        let scroll_top = (self.scroll_top.max(0) as usize).min(max_scroll);
+
+        let first = scroll_top / row_height;
+        let start = first.saturating_sub(OVERSCAN);
+        let end = (first + height.div_ceil(row_height) + 1 + OVERSCAN).min(total);
+
+        VisibleRange {
+            start,
+            end,
+            padding_top: start * row_height,
+            padding_bottom: (total - end) * row_height,
+            scroll_top: scroll_top as i32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrolled(scroll_top: i32) -> Viewport {
+        Viewport {
+            scroll_top,
+            ..Viewport::default()
+        }
+    }
+
+    /// Rows that fit in the default viewport, plus the partly shown one.
+    const ROWS: usize = 7;
+
+    #[test]
+    fn windows_the_top_of_the_list() {
+        let range = scrolled(0).visible(1000);
+        assert_eq!((range.start, range.end), (0, ROWS + OVERSCAN));
+        assert_eq!(range.padding_top, 0);
+        assert_eq!(range.padding_bottom, (1000 - range.end) * 70);
+        assert_eq!(range.scroll_top, 0);
+    }
+
+    #[test]
+    fn windows_the_middle_of_the_list() {
+        let range = scrolled(100 * 70 + 30).visible(1000);
+        assert_eq!(
+            (range.start, range.end),
+            (100 - OVERSCAN, 100 + ROWS + OVERSCAN)
+        );
+        assert_eq!(range.padding_top, range.start * 70);
+        assert_eq!(range.padding_bottom, (1000 - range.end) * 70);
+        assert_eq!(range.scroll_top, 100 * 70 + 30);
+    }
+
+    #[test]
+    fn windows_the_end_of_the_list() {
+        let range = scrolled(i32::MAX).visible(1000);
+        let max_scroll = 1000 * 70 - DEFAULT_VIEWPORT_HEIGHT;
+        assert_eq!(range.scroll_top, max_scroll);
+        assert_eq!(range.start, max_scroll as usize / 70 - OVERSCAN);
+        assert_eq!(range.end, 1000);
+        assert_eq!(range.padding_bottom, 0);
+    }
+
+    #[test]
+    fn renders_a_short_list_whole() {
+        let range = scrolled(0).visible(3);
+        assert_eq!(range, VisibleRange::all(3));
+    }
+
+    #[test]
+    fn clamps_the_scroll_position_to_a_shortened_list() {
+        // Scrolled far down, then a filter leaves ten rows
+        let range = scrolled(500 * 70).visible(10);
+        assert_eq!(range.scroll_top, 10 * 70 - DEFAULT_VIEWPORT_HEIGHT);
+        assert_eq!((range.start, range.end), (0, 10));
+        assert_eq!((range.padding_top, range.padding_bottom), (0, 0));
+
+        // Shorter than the viewport, nothing is left to scroll
+        assert_eq!(scrolled(500 * 70).visible(3), VisibleRange::all(3));
+    }
+}
@@ -0,0 +1,236 @@
+//! Browser glue shared by the wasm apps: URL parameters, `localStorage` and
+//! keyboard shortcuts.
+//!
+//! Everything here also compiles natively for server-side rendering, where
+//! [`window`] is `None` and each helper falls back to its default.
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, KeyboardEvent, Storage, UrlSearchParams, Window};
+
+use crate::seed::SeedConfig;
+use crate::viewport::{Viewport, DEFAULT_ROW_HEIGHT};
+use crate::{storage, Action, Filter, TodoState};
+
+/// `localStorage` key the state is persisted under.
+pub const STORAGE_KEY: &str = "frontend-benchmark-todos";
+
+/// The browser window; always `None` outside wasm, where calling into
+/// JavaScript would panic.
+pub fn window() -> Option<Window> {
+    if cfg!(target_arch = "wasm32") {
+        web_sys::window()
+    } else {
+        None
+    }
+}
+
+/// Returns the value of query parameter `name` in the current URL.
+pub fn query_param(name: &str) -> Option<String> {
+    let search = window()?.location().search().ok()?;
+    UrlSearchParams::new_with_str(&search).ok()?.get(name)
+}
+
+/// `?name`, `?name=1` and `?name=true` turn a flag on; anything else is off.
+pub fn query_flag(name: &str) -> bool {
+    matches!(query_param(name).as_deref(), Some("" | "1" | "true"))
+}
+
+impl SeedConfig {
+    pub fn from_url() -> Self {
+        Self::from_params(query_param)
+    }
+}
+
+impl Viewport {
+    /// `Some` when `?virtual=1` turns on the virtualized list, with the row
+    /// height taken from the shared CSS.
+    pub fn from_url() -> Option<Self> {
+        query_flag("virtual").then(|| Self {
+            row_height: css_row_height().unwrap_or(DEFAULT_ROW_HEIGHT),
+            ..Self::default()
+        })
+    }
+}
+
+/// `--todo-row-height` from `:root` in `shared/styles/todo.css`, in pixels.
+fn css_row_height() -> Option<i32> {
+    let window = window()?;
+    let root = window.document()?.document_element()?;
+    let value = window
+        .get_computed_style(&root)
+        .ok()??
+        .get_property_value("--todo-row-height")
+        .ok()?;
+    value.trim().trim_end_matches("px").parse::<f64>().ok().map(|px| px.round() as i32)
+}
+
+/// How list rows are keyed, switched to non-keyed with `?keyed=0`.
+///
+/// Non-keyed rows are keyed by their position, so every framework reuses
+/// the DOM node at each index and patches its contents, as in
+/// js-framework-benchmark's non-keyed category.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListMode {
+    #[default]
+    Keyed,
+    NonKeyed,
+}
+
+impl ListMode {
+    pub fn from_url() -> Self {
+        match query_param("keyed").as_deref() {
+            Some("0" | "false") => ListMode::NonKeyed,
+            _ => ListMode::Keyed,
+        }
+    }
+
+    /// Key for the row showing todo `id` at visible position `index`.
+    pub fn key(self, index: usize, id: usize) -> usize {
+        match self {
+            ListMode::Keyed => id,
+            ListMode::NonKeyed => index,
+        }
+    }
+}
+
+/// Optional `localStorage` persistence, enabled with `?persist=1`.
+///
+/// It is off by default so cold-start numbers always measure the seeded
+/// list rather than whatever a previous session left behind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Persistence {
+    enabled: bool,
+}
+
+impl Persistence {
+    pub fn from_url() -> Self {
+        Self {
+            enabled: query_flag("persist"),
+        }
+    }
+
+    /// Never loads or saves, whatever the URL says.
+    pub fn disabled() -> Self {
+        Self { enabled: false }
+    }
+
+    pub fn is_enabled(self) -> bool {
+        self.enabled
+    }
+
+    /// The stored state, or `None` when disabled, empty or unreadable.
+    pub fn load(self) -> Option<TodoState> {
+        if !self.enabled {
+            return None;
+        }
+        let json = local_storage()?.get_item(STORAGE_KEY).ok()??;
+        match storage::decode(&json) {
+            Ok(state) => Some(state),
+            Err(err) => {
+                web_sys::console::warn_1(&format!("Ignoring stored todos: {}", err).into());
+                None
+            }
+        }
+    }
+
+    /// The state a page starts from: the stored one, or else `generate`'s,
+    /// with the filter of the hash `route` applied.
+    ///
+    /// A stored state keeps its own filter when the URL has no hash at all,
+    /// as after a `?persist=1` reload of the bare URL. The filter is then
+    /// also returned, so the app can push its route and the URL shows it.
+    pub fn start(
+        self,
+        route: Filter,
+        generate: impl FnOnce() -> TodoState,
+    ) -> (TodoState, Option<Filter>) {
+        let Some(mut state) = self.load() else {
+            let mut state = generate();
+            state.apply(Action::SetFilter(route));
+            return (state, None);
+        };
+        if has_hash() {
+            state.apply(Action::SetFilter(route));
+            return (state, None);
+        }
+        let stored = state.filter;
+        (state, (stored != route).then_some(stored))
+    }
+
+    pub fn save(self, state: &TodoState) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(storage) = local_storage() {
+            let _ = storage.set_item(STORAGE_KEY, &storage::encode(state));
+        }
+    }
+}
+
+/// Whether the current URL has a `#` fragment, even an empty route.
+fn has_hash() -> bool {
+    window()
+        .and_then(|window| window.location().hash().ok())
+        .is_some_and(|hash| !hash.is_empty())
+}
+
+fn local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryShortcut {
+    Undo,
+    Redo,
+}
+
+/// Ctrl+Z (Cmd+Z) undoes and Ctrl+Shift+Z redoes.
+///
+/// Text inputs are left alone so their native text undo keeps working.
+pub fn history_shortcut(event: &KeyboardEvent) -> Option<HistoryShortcut> {
+    if !(event.ctrl_key() || event.meta_key()) || !event.key().eq_ignore_ascii_case("z") {
+        return None;
+    }
+    let in_text_input = event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+        .is_some_and(|input| input.type_() == "text");
+    if in_text_input {
+        return None;
+    }
+    Some(if event.shift_key() {
+        HistoryShortcut::Redo
+    } else {
+        HistoryShortcut::Undo
+    })
+}
+
+/// A window `keydown` listener for undo/redo, removed when dropped.
+pub struct ShortcutListener {
+    closure: Option<Closure<dyn FnMut(KeyboardEvent)>>,
+}
+
+pub fn listen_history_shortcuts(mut handler: impl FnMut(HistoryShortcut) + 'static) -> ShortcutListener {
+    let Some(window) = window() else {
+        return ShortcutListener { closure: None };
+    };
+    let closure = Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+        if let Some(shortcut) = history_shortcut(&event) {
+            event.prevent_default();
+            handler(shortcut);
+        }
+    });
+    let _ = window.add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref());
+    ShortcutListener {
+        closure: Some(closure),
+    }
+}
+
+impl Drop for ShortcutListener {
+    fn drop(&mut self) {
+        if let (Some(window), Some(closure)) = (window(), &self.closure) {
+            let _ = window.remove_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref());
+        }
+    }
+}
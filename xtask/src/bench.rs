@@ -0,0 +1,166 @@
+//! Local counterpart of `benchmark-docker-full.js` for the Rust apps: build
+//! time, bundle sizes and a Lighthouse audit, from `dist/` directories
+//! served by the static server instead of containers.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use implementations::Implementation;
+use serde_json::{json, Map, Value};
+
+use crate::{build, cargo, repo_root, serve};
+
+fn scripts_dir() -> PathBuf {
+    repo_root().join("benchmarks/scripts")
+}
+
+fn results_file() -> PathBuf {
+    repo_root().join("benchmarks/results/local-benchmark-results.json")
+}
+
+/// Benchmarks each implementation and writes the results in the format of
+/// `comprehensive-benchmark-results.json`.
+pub fn bench(implementations: &[&Implementation], build_first: bool) -> Result<(), String> {
+    if !scripts_dir().join("node_modules/lighthouse").exists() {
+        return Err("Lighthouse is not installed; run `npm install` in benchmarks/scripts".into());
+    }
+
+    let mut results = Vec::new();
+    for implementation in implementations {
+        let mut result = Map::new();
+        result.insert("framework".into(), json!(implementation.name));
+        result.insert("type".into(), json!("rust"));
+        if build_first {
+            let elapsed = build::build(implementation)?;
+            result.insert("buildTime".into(), json!(elapsed.as_secs()));
+        }
+        result.extend(bundle_sizes(&crate::dist(implementation))?);
+        results.push(result);
+    }
+
+    {
+        let _servers = serve::start(implementations, true)?;
+        let targets = implementations.iter().map(|implementation| {
+            format!("{}={}", implementation.name, serve::url(implementation))
+        });
+        cargo::run(
+            Command::new("node")
+                .arg("lighthouse-benchmark.js")
+                .args(targets)
+                .current_dir(scripts_dir()),
+        )?;
+    }
+
+    let lighthouse = repo_root().join("benchmarks/results/lighthouse-results.json");
+    let audits: Vec<Value> = std::fs::read_to_string(&lighthouse)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .ok_or_else(|| format!("failed to read {}", lighthouse.display()))?;
+    for result in &mut results {
+        let audit = audits
+            .iter()
+            .find(|audit| audit["name"] == result["framework"])
+            .and_then(Value::as_object);
+        for (key, value) in audit.into_iter().flatten() {
+            if key != "name" {
+                result.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    let path = results_file();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|err| format!("failed to create {}: {}", dir.display(), err))?;
+    }
+    let json = serde_json::to_string_pretty(&results).expect("results serialize to JSON");
+    std::fs::write(&path, json)
+        .map_err(|err| format!("failed to write {}: {}", path.display(), err))?;
+    println!("Results saved to {}", path.display());
+    Ok(())
+}
+
+/// Writes `RESULTS.md` from the last `bench` run.
+pub fn report() -> Result<(), String> {
+    let results = results_file();
+    if !results.exists() {
+        return Err("no local results; run `cargo xtask bench` first".into());
+    }
+    cargo::run(
+        Command::new("node")
+            .arg("generate-report.js")
+            .arg(&results)
+            .current_dir(scripts_dir()),
+    )
+}
+
+/// Sizes by kind as `bundle-size.js` counts them: the gzipped total covers
+/// JavaScript, CSS and WebAssembly, and precompressed copies are skipped.
+fn bundle_sizes(dist: &Path) -> Result<Map<String, Value>, String> {
+    let mut totals: Vec<(&str, usize)> = vec![
+        ("totalJS", 0),
+        ("totalCSS", 0),
+        ("totalWASM", 0),
+        ("totalHTML", 0),
+    ];
+    let mut gzipped = 0;
+    let mut dist_size = 0;
+
+    let mut dirs = vec![dist.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|err| format!("failed to read {}: {}", dir.display(), err))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|err| format!("failed to read {}: {}", dir.display(), err))?
+                .path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+            if ext == "br" || ext == "gz" {
+                continue;
+            }
+            let bytes = std::fs::read(&path)
+                .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+            dist_size += bytes.len();
+            let key = match ext {
+                "js" => "totalJS",
+                "css" => "totalCSS",
+                "wasm" => "totalWASM",
+                "html" => "totalHTML",
+                _ => continue,
+            };
+            if let Some((_, total)) = totals.iter_mut().find(|(name, _)| *name == key) {
+                *total += bytes.len();
+            }
+            if key != "totalHTML" {
+                gzipped += gzip_size(&bytes);
+            }
+        }
+    }
+
+    let mut sizes: Map<String, Value> = totals
+        .into_iter()
+        .map(|(key, size)| (key.to_string(), json!(size)))
+        .collect();
+    sizes.insert("totalGzipped".into(), json!(gzipped));
+    sizes.insert("distSize".into(), json!(dist_size));
+    Ok(sizes)
+}
+
+/// Size at gzip's default level, as `bundle-size.js` measures it.
+fn gzip_size(bytes: &[u8]) -> usize {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(bytes)
+        .expect("writing to a Vec cannot fail");
+    encoder
+        .finish()
+        .expect("writing to a Vec cannot fail")
+        .len()
+}
@@ -0,0 +1,221 @@
+//! One build pipeline for every Rust app, in place of Trunk for Yew and
+//! Leptos and the Dioxus Dockerfile's cargo + wasm-bindgen steps.
+//!
+//! Each `implementations/<name>/dist` ends up holding the same files:
+//!
+//! - `index.html`, the app's own with the bundle's tags added
+//! - `app-<hash>.js` and `app-<hash>_bg.wasm` from wasm-bindgen, plus its
+//!   `snippets/` when the app has any
+//! - `todo-<hash>.css`, the shared stylesheet from `shared/assets`
+//! - a `.br` and a `.gz` next to each of them, for the static server
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use implementations::Implementation;
+use todo_assets::fnv1a;
+
+use crate::cargo;
+
+/// Extensions of the files that are precompressed.
+const COMPRESSED: &[&str] = &["html", "js", "wasm", "css"];
+
+/// Builds `implementation` into its `dist/` and returns how long it took.
+pub fn build(implementation: &Implementation) -> Result<Duration, String> {
+    println!("Building {}", implementation.name);
+    let start = Instant::now();
+    let package = implementation.package.as_deref().unwrap_or_default();
+    let dir = implementation.dir_path();
+
+    let wasm = cargo::build(&[
+        "--release",
+        "--target",
+        "wasm32-unknown-unknown",
+        "--package",
+        package,
+    ])?
+    .into_iter()
+    .filter(|artifact| artifact.kind.iter().any(|kind| kind == "bin"))
+    .flat_map(|artifact| artifact.filenames)
+    .find(|file| file.extension().is_some_and(|ext| ext == "wasm"))
+    .ok_or_else(|| format!("cargo build produced no .wasm for {}", package))?;
+    check_wasm_bindgen()?;
+
+    let dist = crate::dist(implementation);
+    if dist.exists() {
+        std::fs::remove_dir_all(&dist)
+            .map_err(|err| format!("failed to remove {}: {}", dist.display(), err))?;
+    }
+    cargo::run(
+        Command::new("wasm-bindgen")
+            .args(["--target", "web", "--no-typescript", "--out-name", "app"])
+            .arg("--out-dir")
+            .arg(&dist)
+            .arg(&wasm),
+    )?;
+
+    // Hashed names, so the static server can let browsers cache them
+    let js = read(&dist.join("app.js"))?;
+    let hash = fnv1a(&[js.as_slice(), &read(&dist.join("app_bg.wasm"))?].concat());
+    let script = format!("app-{:016x}.js", hash);
+    let module = format!("app-{:016x}_bg.wasm", hash);
+    rename(&dist.join("app.js"), &dist.join(&script))?;
+    rename(&dist.join("app_bg.wasm"), &dist.join(&module))?;
+
+    let index = dir.join("index.html");
+    let page = std::fs::read_to_string(&index)
+        .map_err(|err| format!("failed to read {}: {}", index.display(), err))?;
+    std::fs::write(
+        dist.join("index.html"),
+        with_bundle(&page, &script, &module),
+    )
+    .map_err(|err| format!("failed to write index.html: {}", err))?;
+
+    // The same step Trunk runs as a post_build hook
+    cargo::run(
+        cargo::command()
+            .args(["run", "--quiet", "--package", "todo-assets", "--"])
+            .arg("--app")
+            .arg(&dir)
+            .arg("--dist")
+            .arg(&dist),
+    )?;
+
+    precompress(&dist)?;
+
+    let elapsed = start.elapsed();
+    println!(
+        "Built {} in {:.1}s into {}",
+        implementation.name,
+        elapsed.as_secs_f64(),
+        dist.display()
+    );
+    Ok(elapsed)
+}
+
+/// `page` without its Trunk `<link data-trunk>` tags and with the tags
+/// that load the bundle, as Trunk writes them, at the end of `<head>`.
+fn with_bundle(page: &str, script: &str, module: &str) -> String {
+    let tags = format!(
+        "    <link rel=\"modulepreload\" href=\"/{script}\" crossorigin>\n    \
+         <link rel=\"preload\" href=\"/{module}\" as=\"fetch\" type=\"application/wasm\" crossorigin>\n    \
+         <script type=\"module\">import init from \"/{script}\"; init({{ module_or_path: \"/{module}\" }});</script>\n"
+    );
+    let mut out = String::with_capacity(page.len() + tags.len());
+    for line in page.split_inclusive('\n') {
+        if line.trim_start().starts_with("<link data-trunk") {
+            continue;
+        }
+        if line.trim_start().starts_with("</head>") {
+            out.push_str(&tags);
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Fails unless the `wasm-bindgen` CLI matches the library in the
+/// workspace's lockfile, as the two must be the same version.
+fn check_wasm_bindgen() -> Result<(), String> {
+    let lockfile = crate::repo_root().join("Cargo.lock");
+    let lock = std::fs::read_to_string(&lockfile)
+        .map_err(|err| format!("failed to read {}: {}", lockfile.display(), err))?;
+    let library = lock
+        .split("[[package]]")
+        .find(|package| package.contains("\nname = \"wasm-bindgen\"\n"))
+        .and_then(|package| package.split("\nversion = \"").nth(1))
+        .and_then(|rest| rest.split('"').next())
+        .ok_or("no wasm-bindgen in Cargo.lock")?;
+
+    let install = format!(
+        "install it with `cargo install wasm-bindgen-cli --version {} --locked`",
+        library
+    );
+    let output = Command::new("wasm-bindgen")
+        .arg("--version")
+        .output()
+        .map_err(|_| format!("wasm-bindgen not found; {}", install))?;
+    let cli = String::from_utf8_lossy(&output.stdout);
+    let cli = cli.split_whitespace().nth(1).unwrap_or("unknown");
+    if cli == library {
+        Ok(())
+    } else {
+        Err(format!(
+            "wasm-bindgen {} is installed but the apps use {}; {}",
+            cli, library, install
+        ))
+    }
+}
+
+/// Writes a `.br` and a `.gz` next to every file in `dir` that is worth
+/// compressing, at the highest levels.
+fn precompress(dir: &Path) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|err| format!("failed to read {}: {}", dir.display(), err))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|err| format!("failed to read {}: {}", dir.display(), err))?
+            .path();
+        if path.is_dir() {
+            precompress(&path)?;
+            continue;
+        }
+        let compress = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| COMPRESSED.contains(&ext));
+        if !compress {
+            continue;
+        }
+        let bytes = read(&path)?;
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::best());
+        gzip.write_all(&bytes)
+            .expect("writing to a Vec cannot fail");
+        let gzip = gzip.finish().expect("writing to a Vec cannot fail");
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+        brotli
+            .write_all(&bytes)
+            .expect("writing to a Vec cannot fail");
+        let brotli = brotli.into_inner();
+
+        for (ext, compressed) in [("gz", gzip), ("br", brotli)] {
+            let mut name = path.clone().into_os_string();
+            name.push(".");
+            name.push(ext);
+            let name = PathBuf::from(name);
+            std::fs::write(&name, compressed)
+                .map_err(|err| format!("failed to write {}: {}", name.display(), err))?;
+        }
+    }
+    Ok(())
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|err| format!("failed to read {}: {}", path.display(), err))
+}
+
+fn rename(from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::rename(from, to).map_err(|err| format!("failed to rename {}: {}", from.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_trunk_links_with_the_bundle() {
+        let page = "<head>\n    <title>Todo</title>\n    <link data-trunk rel=\"rust\" />\n</head>\n<body>\n    <div id=\"main\"></div>\n</body>\n";
+        let built = with_bundle(page, "app-1.js", "app-1_bg.wasm");
+        assert!(!built.contains("data-trunk"));
+        assert!(built.contains(
+            "    <script type=\"module\">import init from \"/app-1.js\"; init({ module_or_path: \"/app-1_bg.wasm\" });</script>\n</head>\n"
+        ));
+        assert!(built.ends_with("<body>\n    <div id=\"main\"></div>\n</body>\n"));
+    }
+}
@@ -0,0 +1,96 @@
+//! Running cargo and the other tools the commands shell out to.
+
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// The cargo that runs xtask, in the repository root.
+pub fn command() -> Command {
+    let mut command = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command.current_dir(crate::repo_root());
+    command
+}
+
+/// Runs `command` to completion with its output shown.
+pub fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .status()
+        .map_err(|err| format!("failed to run {}: {}", program, err))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+/// Files a `cargo build` produced for one target.
+pub struct Artifact {
+    pub kind: Vec<String>,
+    pub filenames: Vec<PathBuf>,
+    pub executable: Option<PathBuf>,
+}
+
+/// Runs `cargo build` with `args` and returns what it built for the
+/// workspace's own packages. Diagnostics still go to the terminal.
+pub fn build(args: &[&str]) -> Result<Vec<Artifact>, String> {
+    let mut child = command()
+        .arg("build")
+        .args(args)
+        .arg("--message-format=json-render-diagnostics")
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("failed to run cargo: {}", err))?;
+
+    let root = crate::repo_root();
+    let mut artifacts = Vec::new();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    for line in BufReader::new(stdout).lines() {
+        let line = line.map_err(|err| format!("failed to read cargo output: {}", err))?;
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if message["reason"] != "compiler-artifact" {
+            continue;
+        }
+        let in_workspace = message["manifest_path"]
+            .as_str()
+            .is_some_and(|manifest| PathBuf::from(manifest).starts_with(&root));
+        if !in_workspace {
+            continue;
+        }
+        let strings = |value: &serde_json::Value| -> Vec<String> {
+            value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        };
+        artifacts.push(Artifact {
+            kind: strings(&message["target"]["kind"]),
+            filenames: strings(&message["filenames"])
+                .into_iter()
+                .map(PathBuf::from)
+                .collect(),
+            executable: message["executable"].as_str().map(PathBuf::from),
+        });
+    }
+
+    let status = child
+        .wait()
+        .map_err(|err| format!("failed to wait for cargo: {}", err))?;
+    if status.success() {
+        Ok(artifacts)
+    } else {
+        Err(format!("cargo build {} failed", args.join(" ")))
+    }
+}
+
+/// The executable of the binary package `package`, built in release mode.
+pub fn build_tool(package: &str) -> Result<PathBuf, String> {
+    build(&["--release", "--package", package])?
+        .into_iter()
+        .find_map(|artifact| artifact.executable)
+        .ok_or_else(|| format!("cargo build produced no executable for {}", package))
+}
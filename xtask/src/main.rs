@@ -0,0 +1,135 @@
+//! Builds, serves and benchmarks the Rust implementations the same way on
+//! any machine, without Docker: `cargo xtask <command>`.
+//!
+//! `build` gives every app the same `dist/` layout, so bundle sizes and
+//! load times compare the frameworks rather than their build tools.
+
+mod bench;
+mod build;
+mod cargo;
+mod serve;
+
+use std::path::{Path, PathBuf};
+
+use implementations::{Implementation, Manifest};
+
+const USAGE: &str = "\
+Usage: cargo xtask <COMMAND>
+
+Commands:
+  build <IMPL|all>          Build dist/ for one Rust app, or all of them
+  serve [IMPL|all]          Serve the built dist/ directories on their Docker ports
+  bench [IMPL|all]          Build, measure bundle sizes and run Lighthouse on each app
+  report                    Write RESULTS.md from the last `bench` run
+  check-conformance         Check that the apps render the DOM in the spec
+
+Options:
+  --no-build                With `bench`, measure the existing dist/ directories
+  -h, --help                Print this help";
+
+/// The `dist/` of a Rust app, which the manifest always gives them.
+pub fn dist(implementation: &Implementation) -> PathBuf {
+    implementation
+        .dist_path()
+        .expect("implementations.toml gives every Rust app a dist")
+}
+
+pub fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is inside the repository")
+        .to_path_buf()
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    let command = args.remove(0);
+    let no_build = take_flag(&mut args, "--no-build");
+    if no_build && command != "bench" {
+        usage_error("--no-build only applies to bench");
+    }
+
+    let which = match args.as_slice() {
+        [] => None,
+        [which] if matches!(command.as_str(), "build" | "serve" | "bench") => Some(which.as_str()),
+        _ => usage_error(&format!("unexpected arguments: {}", args.join(" "))),
+    };
+
+    let manifest = Manifest::load().unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+    let result = match command.as_str() {
+        "build" => {
+            let Some(which) = which else {
+                usage_error(&format!(
+                    "build needs one of {} or all",
+                    app_names(&manifest)
+                ));
+            };
+            select(&manifest, Some(which)).and_then(|selected| {
+                for implementation in selected {
+                    build::build(implementation)?;
+                }
+                Ok(())
+            })
+        }
+        "serve" => select(&manifest, which).and_then(|selected| serve::serve(&selected)),
+        "bench" => select(&manifest, which).and_then(|selected| bench::bench(&selected, !no_build)),
+        "report" => bench::report(),
+        "check-conformance" => check_conformance(),
+        _ => usage_error(&format!("unknown command {}", command)),
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+/// The Rust apps named by `which`, all of them for `all` or none.
+fn select<'a>(
+    manifest: &'a Manifest,
+    which: Option<&str>,
+) -> Result<Vec<&'a Implementation>, String> {
+    match which {
+        None | Some("all") => Ok(manifest.wasm_apps().collect()),
+        Some(name) => manifest
+            .wasm_apps()
+            .find(|implementation| implementation.name == name)
+            .map(|implementation| vec![implementation])
+            .ok_or_else(|| {
+                format!(
+                    "unknown implementation {}, expected {} or all",
+                    name,
+                    app_names(manifest)
+                )
+            }),
+    }
+}
+
+fn app_names(manifest: &Manifest) -> String {
+    let names: Vec<&str> = manifest.wasm_apps().map(|app| app.name.as_str()).collect();
+    names.join(", ")
+}
+
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != before
+}
+
+/// Runs the conformance tests, which render each app natively and compare
+/// its markup with `benchmarks/tools/conformance/spec.toml`.
+fn check_conformance() -> Result<(), String> {
+    cargo::run(cargo::command().args(["test", "--package", "conformance"]))
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    std::process::exit(2);
+}
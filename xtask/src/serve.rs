@@ -0,0 +1,91 @@
+//! Serving the built apps with `benchmarks/tools/static-server`.
+
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use implementations::Implementation;
+
+use crate::cargo;
+
+/// Static servers started for some implementations; dropping them stops
+/// them.
+pub struct Servers(Vec<Child>);
+
+impl Drop for Servers {
+    fn drop(&mut self) {
+        for child in &mut self.0 {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Serves each implementation's `dist/` on its Docker port until stopped.
+pub fn serve(implementations: &[&Implementation]) -> Result<(), String> {
+    let mut servers = start(implementations, false)?;
+    for child in &mut servers.0 {
+        child
+            .wait()
+            .map_err(|err| format!("failed to wait for the server: {}", err))?;
+    }
+    Ok(())
+}
+
+/// Starts a static server for each implementation and waits until they
+/// all accept connections. `quiet` drops their request logs.
+pub fn start(implementations: &[&Implementation], quiet: bool) -> Result<Servers, String> {
+    for implementation in implementations {
+        if !crate::dist(implementation).join("index.html").exists() {
+            return Err(format!(
+                "{} is not built; run `cargo xtask build {}` first",
+                implementation.name, implementation.name
+            ));
+        }
+    }
+    let server = cargo::build_tool("static-server")?;
+
+    let mut servers = Servers(Vec::new());
+    for implementation in implementations {
+        let child = Command::new(&server)
+            .arg(crate::dist(implementation))
+            .args(["--listen", &listen_addr(implementation)])
+            .stdout(if quiet {
+                Stdio::null()
+            } else {
+                Stdio::inherit()
+            })
+            .spawn()
+            .map_err(|err| format!("failed to run {}: {}", server.display(), err))?;
+        servers.0.push(child);
+    }
+
+    for (implementation, child) in implementations.iter().zip(&mut servers.0) {
+        let address = listen_addr(implementation);
+        let start = Instant::now();
+        while TcpStream::connect(&address).is_err() {
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(format!(
+                    "the {} server exited with {}",
+                    implementation.name, status
+                ));
+            }
+            if start.elapsed() > Duration::from_secs(10) {
+                return Err(format!(
+                    "{} did not start on {}",
+                    implementation.name, address
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    Ok(servers)
+}
+
+pub fn url(implementation: &Implementation) -> String {
+    format!("http://{}", listen_addr(implementation))
+}
+
+fn listen_addr(implementation: &Implementation) -> String {
+    format!("127.0.0.1:{}", implementation.port)
+}